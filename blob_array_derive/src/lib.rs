@@ -0,0 +1,296 @@
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Generates a struct of arrays container for a struct with named fields.
+///
+/// For `struct Obj { name: String, age: u32 }` this generates `ObjSoa`, which stores every field
+/// in its own `BlobArray` and keeps them in lockstep, together with the row views `ObjSoaRef` and `ObjSoaMut`.
+/// `ObjSoa` has `push`, `pop`, `swap_remove`, `get`, `iter`, `iter_mut`, and a slice accessor per field
+/// (`name()`, `name_mut()`, `name_array()`). Fields whose accessors would clash with one of the container's
+/// methods, such as `len` or `get`, are rejected with a compile error.
+#[proc_macro_derive(BlobSoa)]
+pub fn derive_blob_soa(input: TokenStream) -> TokenStream {
+    match parse_struct(input) {
+        Ok(input) => expand(&input).parse().unwrap(),
+        Err(message) => format!("::core::compile_error!({message:?});").parse().unwrap(),
+    }
+}
+
+struct Field {
+    name: String,
+    ty: String,
+}
+
+struct Struct {
+    vis: String,
+    name: String,
+    fields: Vec<Field>,
+}
+
+fn parse_struct(input: TokenStream) -> Result<Struct, String> {
+    let mut tokens = input.into_iter().peekable();
+    skip_attributes(&mut tokens);
+    let vis = parse_visibility(&mut tokens);
+
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {}
+        _ => return Err("BlobSoa can only be derived for structs".to_string()),
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("expected a struct name".to_string()),
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            let fields = parse_fields(group.stream())?;
+            if fields.is_empty() {
+                return Err("BlobSoa needs at least one field".to_string())
+            }
+            check_accessor_names(&name, &fields)?;
+            Ok(Struct { vis, name, fields })
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            Err("BlobSoa doesn't support generic structs".to_string())
+        }
+        _ => Err("BlobSoa can only be derived for structs with named fields".to_string()),
+    }
+}
+
+/// Methods the container always has, which the per field accessors must not collide with.
+const CONTAINER_METHODS: &[&str] = &[
+    "new", "with_capacity", "len", "is_empty", "reserve", "push", "pop", "swap_remove",
+    "get", "get_mut", "iter", "iter_mut", "clear",
+];
+
+/// Every field gets `{field}`, `{field}_mut` and `{field}_array`, which can't share a name
+/// with a container method or with another field's accessor.
+fn check_accessor_names(name: &str, fields: &[Field]) -> Result<(), String> {
+    let mut taken = CONTAINER_METHODS.iter().map(|method| (method.to_string(), None)).collect::<Vec<_>>();
+    for field in fields {
+        for accessor in [field.name.clone(), format!("{}_mut", field.name), format!("{}_array", field.name)] {
+            match taken.iter().find(|(method, _)| *method == accessor) {
+                Some((_, None)) => return Err(format!(
+                    "BlobSoa: field `{}` would generate `{name}Soa::{accessor}`, which clashes with the container's own method, rename the field",
+                    field.name,
+                )),
+                Some((_, Some(other))) => return Err(format!(
+                    "BlobSoa: fields `{other}` and `{}` would both generate `{name}Soa::{accessor}`, rename one of them",
+                    field.name,
+                )),
+                None => taken.push((accessor, Some(field.name.clone()))),
+            }
+        }
+    }
+    Ok(())
+}
+
+type Tokens = std::iter::Peekable<proc_macro::token_stream::IntoIter>;
+
+fn skip_attributes(tokens: &mut Tokens) {
+    while matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+        tokens.next();
+        tokens.next();
+    }
+}
+
+fn parse_visibility(tokens: &mut Tokens) -> String {
+    match tokens.peek() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+            tokens.next();
+            match tokens.peek() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    let restriction = group.to_string();
+                    tokens.next();
+                    format!("pub {restriction}")
+                }
+                _ => "pub".to_string(),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+fn parse_fields(stream: TokenStream) -> Result<Vec<Field>, String> {
+    let mut tokens = stream.into_iter().peekable();
+    let mut fields = Vec::new();
+
+    loop {
+        skip_attributes(&mut tokens);
+        parse_visibility(&mut tokens);
+
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident.to_string(),
+            None => return Ok(fields),
+            _ => return Err("expected a field name".to_string()),
+        };
+
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+            _ => return Err(format!("expected `:` after field `{name}`")),
+        }
+
+        // the type ends at the first comma outside of angle brackets, `->` doesn't close one
+        let mut ty = TokenStream::new();
+        let mut depth = 0usize;
+        let mut after_dash = false;
+        while let Some(token) = tokens.next_if(|token| {
+            !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ',' && depth == 0)
+        }) {
+            if let TokenTree::Punct(punct) = &token {
+                match punct.as_char() {
+                    '<' => depth += 1,
+                    '>' if !after_dash => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                after_dash = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+            } else {
+                after_dash = false;
+            }
+            ty.extend([token]);
+        }
+        tokens.next();
+
+        fields.push(Field { name, ty: ty.to_string() });
+    }
+}
+
+fn expand(input: &Struct) -> String {
+    let Struct { vis, name, fields } = input;
+    let soa = format!("{name}Soa");
+    let soa_ref = format!("{name}SoaRef");
+    let soa_mut = format!("{name}SoaMut");
+    let first = &fields[0].name;
+
+    let join = |f: &dyn Fn(&Field) -> String| fields.iter().map(f).collect::<String>();
+    let names = join(&|field| format!("{}, ", field.name));
+    // `((a, b), c)` for iterators zipped left to right
+    let zipped_pattern = fields[1..].iter().fold(first.clone(), |pattern, field| format!("({pattern}, {})", field.name));
+    let zipped = |method: &str| {
+        fields[1..].iter().fold(format!("{first}.{method}()"), |iter, field| {
+            format!("{iter}.zip({}.{method}())", field.name)
+        })
+    };
+
+    let soa_fields = join(&|field| format!("{}: ::blob_array::BlobArray,", field.name));
+    let ref_fields = join(&|field| format!("pub {}: &'a {},", field.name, field.ty));
+    let mut_fields = join(&|field| format!("pub {}: &'a mut {},", field.name, field.ty));
+    let new_fields = join(&|field| format!("{}: ::blob_array::BlobArray::new::<{}>(capacity),", field.name, field.ty));
+    let reserve = join(&|field| format!("self.{}.reserve(additional);", field.name));
+    let push = join(&|field| format!("self.{0}.push::<{1}>({0});", field.name, field.ty));
+    let pop = join(&|field| format!("{0}: self.{0}.pop::<{1}>().unwrap(),", field.name, field.ty));
+    let swap_remove = join(&|field| format!("{0}: self.{0}.swap_remove::<{1}>(index).unwrap(),", field.name, field.ty));
+    let get = join(&|field| format!("{0}: self.{0}.get::<{1}>(index).unwrap(),", field.name, field.ty));
+    let get_mut = join(&|field| format!("{0}: self.{0}.get_mut::<{1}>(index).unwrap(),", field.name, field.ty));
+    let slices = join(&|field| format!("let {0} = self.{0}.as_slice::<{1}>();", field.name, field.ty));
+    let mut_slices = join(&|field| format!("let {0} = self.{0}.as_mut_slice::<{1}>();", field.name, field.ty));
+    let columns = join(&|field| format!("&mut self.{}, ", field.name));
+    let accessors = join(&|field| format!(
+        "pub fn {0}(&self) -> &[{1}] {{ self.{0}.as_slice::<{1}>() }}
+        pub fn {0}_mut(&mut self) -> &mut [{1}] {{ self.{0}.as_mut_slice::<{1}>() }}
+        pub fn {0}_array(&self) -> &::blob_array::BlobArray {{ &self.{0} }}",
+        field.name, field.ty,
+    ));
+    let iter = zipped("iter");
+    let iter_mut = zipped("iter_mut");
+
+    format!(r#"
+        /// Struct of arrays storage for [`{name}`], every field lives in its own `BlobArray`.
+        {vis} struct {soa} {{ {soa_fields} }}
+
+        /// A row of [`{soa}`].
+        #[derive(Debug, Clone, Copy)]
+        {vis} struct {soa_ref}<'a> {{ {ref_fields} }}
+
+        /// A mutable row of [`{soa}`].
+        #[derive(Debug)]
+        {vis} struct {soa_mut}<'a> {{ {mut_fields} }}
+
+        impl ::core::default::Default for {soa} {{
+            fn default() -> Self {{ Self::new() }}
+        }}
+
+        #[allow(dead_code)]
+        impl {soa} {{
+            pub fn new() -> Self {{ Self::with_capacity(0) }}
+
+            pub fn with_capacity(capacity: usize) -> Self {{ Self {{ {new_fields} }} }}
+
+            pub fn len(&self) -> usize {{ self.{first}.len() }}
+
+            pub fn is_empty(&self) -> bool {{ self.{first}.is_empty() }}
+
+            pub fn reserve(&mut self, additional: usize) {{ {reserve} }}
+
+            pub fn push(&mut self, value: {name}) {{
+                // reserving up front means the pushes can't fail halfway through a row
+                self.reserve(1);
+                let {name} {{ {names} }} = value;
+                {push}
+            }}
+
+            pub fn pop(&mut self) -> ::core::option::Option<{name}> {{
+                if self.is_empty() {{ return ::core::option::Option::None }}
+                ::core::option::Option::Some({name} {{ {pop} }})
+            }}
+
+            #[track_caller]
+            pub fn swap_remove(&mut self, index: usize) -> {name} {{
+                let len = self.len();
+                assert!(index < len, "swap_remove index (is {{index}}) should be < len (is {{len}})");
+                {name} {{ {swap_remove} }}
+            }}
+
+            pub fn get(&self, index: usize) -> ::core::option::Option<{soa_ref}<'_>> {{
+                if index >= self.len() {{ return ::core::option::Option::None }}
+                ::core::option::Option::Some({soa_ref} {{ {get} }})
+            }}
+
+            pub fn get_mut(&mut self, index: usize) -> ::core::option::Option<{soa_mut}<'_>> {{
+                if index >= self.len() {{ return ::core::option::Option::None }}
+                ::core::option::Option::Some({soa_mut} {{ {get_mut} }})
+            }}
+
+            pub fn iter(&self) -> impl ::core::iter::Iterator<Item = {soa_ref}<'_>> {{
+                {slices}
+                {iter}.map(|{zipped_pattern}| {soa_ref} {{ {names} }})
+            }}
+
+            pub fn iter_mut(&mut self) -> impl ::core::iter::Iterator<Item = {soa_mut}<'_>> {{
+                {mut_slices}
+                {iter_mut}.map(|{zipped_pattern}| {soa_mut} {{ {names} }})
+            }}
+
+            pub fn clear(&mut self) {{
+                struct Guard<'a, 'b>(::core::slice::IterMut<'a, &'b mut ::blob_array::BlobArray>);
+
+                impl ::core::ops::Drop for Guard<'_, '_> {{
+                    fn drop(&mut self) {{
+                        for column in self.0.by_ref() {{ column.clear() }}
+                    }}
+                }}
+
+                // if a drop fn panics, the guard clears the remaining columns so they all stay empty
+                let mut columns = [{columns}];
+                let mut guard = Guard(columns.iter_mut());
+                while let ::core::option::Option::Some(column) = guard.0.next() {{ column.clear() }}
+            }}
+
+            {accessors}
+        }}
+
+        impl ::core::iter::Extend<{name}> for {soa} {{
+            fn extend<I: ::core::iter::IntoIterator<Item = {name}>>(&mut self, iter: I) {{
+                for value in iter {{ self.push(value) }}
+            }}
+        }}
+
+        impl ::core::iter::FromIterator<{name}> for {soa} {{
+            fn from_iter<I: ::core::iter::IntoIterator<Item = {name}>>(iter: I) -> Self {{
+                let mut soa = Self::new();
+                soa.extend(iter);
+                soa
+            }}
+        }}
+    "#)
+}
@@ -0,0 +1,114 @@
+use blob_array_derive::BlobSoa;
+
+#[derive(Debug, PartialEq, BlobSoa)]
+struct Obj {
+    name: String,
+    age: u32,
+}
+
+#[derive(Debug, BlobSoa)]
+pub struct Particle {
+    #[allow(dead_code)]
+    pub position: [f32; 3],
+    pub(crate) lookup: std::collections::HashMap<u32, Vec<u8>>,
+    callback: Option<fn(u32) -> u32>,
+}
+
+#[derive(Debug, PartialEq, BlobSoa)]
+struct Single {
+    value: u64,
+}
+
+#[derive(Debug)]
+struct Bomb(bool);
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        if self.0 {
+            panic!("boom");
+        }
+    }
+}
+
+#[derive(Debug, BlobSoa)]
+struct Armed {
+    bomb: Bomb,
+    name: String,
+}
+
+fn obj(name: &str, age: u32) -> Obj {
+    Obj { name: name.to_string(), age }
+}
+
+#[test]
+fn push_and_remove() {
+    let mut soa = ObjSoa::new();
+    soa.push(obj("Ochi", 1));
+    soa.push(obj("Nana", 2));
+    soa.push(obj("Hachi", 3));
+
+    assert_eq!(soa.len(), 3);
+    assert_eq!(soa.age(), &[1, 2, 3]);
+    assert_eq!(soa.name_array().len(), 3);
+
+    assert_eq!(soa.swap_remove(0), obj("Ochi", 1));
+    assert_eq!(soa.age(), &[3, 2]);
+    assert_eq!(soa.name(), &["Hachi".to_string(), "Nana".to_string()]);
+
+    let row = soa.get(1).unwrap();
+    assert_eq!((row.name.as_str(), *row.age), ("Nana", 2));
+    assert!(soa.get(2).is_none());
+
+    assert_eq!(soa.pop(), Some(obj("Nana", 2)));
+    assert_eq!(soa.len(), 1);
+    soa.clear();
+    assert!(soa.is_empty() && soa.pop().is_none());
+}
+
+#[test]
+fn iterate_rows_and_columns() {
+    let mut soa = (0..10).map(|i| obj(&i.to_string(), i)).collect::<ObjSoa>();
+
+    for row in soa.iter_mut() {
+        *row.age *= 2;
+        row.name.push('!');
+    }
+    soa.age_mut()[0] = 100;
+
+    assert_eq!(soa.age().iter().sum::<u32>(), 100 + (1..10).map(|i| i * 2).sum::<u32>());
+    let rows = soa.iter().map(|row| (row.name.clone(), *row.age)).collect::<Vec<_>>();
+    assert_eq!(rows[3], ("3!".to_string(), 6));
+
+    let row = soa.get_mut(1).unwrap();
+    *row.age = 7;
+    assert_eq!(soa.age()[1], 7);
+}
+
+#[test]
+fn field_types() {
+    fn double(value: u32) -> u32 { value * 2 }
+
+    let mut particles = ParticleSoa::with_capacity(4);
+    particles.push(Particle {
+        position: [1.0, 2.0, 3.0],
+        lookup: [(1, vec![1])].into_iter().collect(),
+        callback: Some(double),
+    });
+    assert_eq!(particles.position(), &[[1.0, 2.0, 3.0]]);
+    assert_eq!((particles.callback()[0].unwrap())(4), 8);
+
+    let mut singles = SingleSoa::default();
+    singles.extend([Single { value: 1 }, Single { value: 2 }]);
+    assert_eq!(singles.iter().map(|row| *row.value).collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn clear_panic() {
+    let mut soa = ArmedSoa::new();
+    soa.push(Armed { bomb: Bomb(true), name: "Ochi".to_string() });
+    soa.push(Armed { bomb: Bomb(false), name: "Nana".to_string() });
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| soa.clear()));
+    assert!(result.is_err());
+    assert!(soa.is_empty() && soa.name().is_empty());
+}
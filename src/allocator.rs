@@ -0,0 +1,143 @@
+use std::alloc::{self, Layout};
+use std::ptr::NonNull;
+
+/// The allocator could not satisfy a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl std::fmt::Display for AllocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+impl std::error::Error for AllocError {}
+
+/// Memory source of a [`BlobArray`](crate::BlobArray), shaped after the unstable `std::alloc::Allocator`
+/// (and `allocator-api2`) so arenas, bump allocators and per-frame scratch allocators can be plugged in.
+///
+/// # Safety
+/// Blocks returned by `allocate`, `grow` and `shrink` must stay valid until they are passed to `deallocate`,
+/// `grow` or `shrink`, and must fit the requested layout.
+pub unsafe trait Allocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+    /// # Safety
+    /// `ptr` must have been allocated by this allocator with `layout`.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// # Safety
+    /// `ptr` must have been allocated by this allocator with `old_layout`,
+    /// and `new_layout.size()` must be greater than or equal to `old_layout.size()`.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let new_block = self.allocate(new_layout)?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(ptr.as_ptr(), new_block.cast::<u8>().as_ptr(), old_layout.size());
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_block)
+    }
+
+    /// # Safety
+    /// `ptr` must have been allocated by this allocator with `old_layout`,
+    /// and `new_layout.size()` must be smaller than or equal to `old_layout.size()`.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let new_block = self.allocate(new_layout)?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(ptr.as_ptr(), new_block.cast::<u8>().as_ptr(), new_layout.size());
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_block)
+    }
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { (**self).grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { (**self).shrink(ptr, old_layout, new_layout) }
+    }
+}
+
+/// The global memory allocator, `std::alloc::alloc` and friends.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Global;
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let raw = unsafe { alloc::alloc(layout) };
+        NonNull::new(raw)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { alloc::dealloc(ptr.as_ptr(), layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { self.realloc(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { self.realloc(ptr, old_layout, new_layout) }
+    }
+}
+
+impl Global {
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if old_layout.align() != new_layout.align() {
+            return Err(AllocError)
+        }
+
+        let raw = unsafe { alloc::realloc(ptr.as_ptr(), old_layout, new_layout.size()) };
+        NonNull::new(raw)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+            .ok_or(AllocError)
+    }
+}
@@ -0,0 +1,392 @@
+use std::alloc::Layout;
+use std::any::TypeId;
+use std::ptr::NonNull;
+
+use crate::{Allocator, BlobArray, BlobArrayError, Global};
+
+/// A [`BlobArray`] split into fixed size chunks, which are never reallocated.
+/// Pushing only ever adds chunks, so pointers to existing elements stay valid until those elements are removed.
+///
+/// Removal follows `BlobArray`: [`ChunkedBlobArray::swap_remove`] moves the last element into the hole,
+/// every other element stays where it is.
+pub struct ChunkedBlobArray<A: Allocator + Clone = Global> {
+    /// Empty array every chunk is cloned from, it carries the element type.
+    prototype: BlobArray<A>,
+    /// Chunks past the last element are kept around empty after removals, to be refilled.
+    chunks: Vec<BlobArray<A>>,
+    chunk_len: usize,
+    len: usize,
+}
+
+impl ChunkedBlobArray {
+    #[track_caller]
+    pub fn new<T: 'static>(chunk_len: usize) -> Self {
+        Self::new_in::<T>(chunk_len, Global)
+    }
+
+    /// # Safety
+    /// See [`BlobArray::from_layout`].
+    #[track_caller]
+    pub unsafe fn from_layout(
+        layout: Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        type_id: Option<TypeId>,
+        chunk_len: usize,
+    ) -> Self {
+        unsafe { Self::from_layout_in(layout, drop, type_id, chunk_len, Global) }
+    }
+}
+
+impl<A: Allocator + Clone> ChunkedBlobArray<A> {
+    #[track_caller]
+    pub fn new_in<T: 'static>(chunk_len: usize, alloc: A) -> Self {
+        Self::from_prototype(BlobArray::new_in::<T>(0, alloc), chunk_len)
+    }
+
+    /// # Safety
+    /// See [`BlobArray::from_layout`].
+    #[track_caller]
+    pub unsafe fn from_layout_in(
+        layout: Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        type_id: Option<TypeId>,
+        chunk_len: usize,
+        alloc: A,
+    ) -> Self {
+        Self::from_prototype(unsafe { BlobArray::from_layout_in(layout, drop, type_id, 0, alloc) }, chunk_len)
+    }
+
+    #[track_caller]
+    fn from_prototype(prototype: BlobArray<A>, chunk_len: usize) -> Self {
+        assert!(chunk_len != 0, "chunk length must be non-zero");
+        Self {
+            prototype,
+            chunks: Vec::new(),
+            chunk_len,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.chunks.len() * self.chunk_len
+    }
+
+    pub fn chunk_len(&self) -> usize {
+        self.chunk_len
+    }
+
+    pub fn item_layout(&self) -> Layout {
+        self.prototype.item_layout()
+    }
+
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.prototype.type_id()
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.prototype.type_name()
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.prototype.is::<T>()
+    }
+
+    /// The chunk and the offset within it where the element at `index` lives.
+    pub fn locate(&self, index: usize) -> (usize, usize) {
+        (index / self.chunk_len, index % self.chunk_len)
+    }
+
+    /// Makes sure `additional` more elements can be pushed without allocating.
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), BlobArrayError> {
+        let required = self.len.checked_add(additional).ok_or(BlobArrayError::CapacityOverflow)?;
+        while self.capacity() < required {
+            self.chunks.push(self.prototype.empty_like(self.chunk_len)?);
+        }
+        Ok(())
+    }
+
+    /// The chunk the next element goes into, the chunks are never grown past `chunk_len`.
+    fn tail_chunk(&mut self) -> Result<&mut BlobArray<A>, BlobArrayError> {
+        self.try_reserve(1)?;
+        let (chunk, _) = self.locate(self.len);
+        Ok(&mut self.chunks[chunk])
+    }
+
+    #[track_caller]
+    pub fn push<T: 'static>(&mut self, value: T) {
+        self.try_push(value).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_push<T: 'static>(&mut self, value: T) -> Result<(), BlobArrayError> {
+        self.prototype.check_type::<T>()?;
+        self.tail_chunk()?.try_push(value)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// # Safety
+    /// See [`BlobArray::push_raw`].
+    #[track_caller]
+    pub unsafe fn push_raw(&mut self, value: *const u8) {
+        let chunk = self.tail_chunk().unwrap_or_else(|err| err.handle());
+        unsafe { chunk.push_raw(value) };
+        self.len += 1;
+    }
+
+    #[track_caller]
+    pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.prototype.assert_type::<T>();
+        if index >= self.len { return None }
+
+        let (chunk, offset) = self.locate(index);
+        self.chunks[chunk].get(offset)
+    }
+
+    #[track_caller]
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        self.prototype.assert_type::<T>();
+        if index >= self.len { return None }
+
+        let (chunk, offset) = self.locate(index);
+        self.chunks[chunk].get_mut(offset)
+    }
+
+    /// The pointer stays valid until the element is removed, pushing never moves it.
+    #[track_caller]
+    pub fn get_ptr(&self, index: usize) -> NonNull<u8> {
+        if index >= self.len {
+            BlobArrayError::IndexOutOfBounds { index, len: self.len }.handle()
+        }
+
+        let (chunk, offset) = self.locate(index);
+        self.chunks[chunk].get_ptr(offset)
+    }
+
+    #[track_caller]
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        self.prototype.assert_type::<T>();
+        if self.len == 0 { return None }
+
+        let (chunk, _) = self.locate(self.len - 1);
+        self.len -= 1;
+        self.chunks[chunk].pop()
+    }
+
+    pub fn pop_and_drop(&mut self) -> bool {
+        if self.len == 0 { return false }
+
+        let (chunk, _) = self.locate(self.len - 1);
+        self.len -= 1;
+        self.chunks[chunk].pop_and_drop()
+    }
+
+    /// Moves the last element into `index`.
+    #[track_caller]
+    pub fn swap_remove<T: 'static>(&mut self, index: usize) -> Option<T> {
+        self.prototype.assert_type::<T>();
+        if index >= self.len { return None }
+
+        let last = self.pop::<T>()?;
+        match self.get_mut::<T>(index) {
+            Some(slot) => Some(std::mem::replace(slot, last)),
+            None => Some(last),
+        }
+    }
+
+    /// Moves the last element into `out`, returning `false` if the array is empty.
+    ///
+    /// # Safety
+    /// See [`BlobArray::pop_raw`].
+    pub unsafe fn pop_raw(&mut self, out: *mut u8) -> bool {
+        if self.len == 0 { return false }
+
+        let (chunk, _) = self.locate(self.len - 1);
+        self.len -= 1;
+        unsafe { self.chunks[chunk].pop_raw(out) }
+    }
+
+    /// Moves the value at `index` into `out`, replacing it with the last element.
+    ///
+    /// # Safety
+    /// See [`BlobArray::swap_remove_raw`].
+    #[track_caller]
+    pub unsafe fn swap_remove_raw(&mut self, index: usize, out: *mut u8) {
+        self.swap_with_last(index);
+        unsafe { self.pop_raw(out) };
+    }
+
+    #[track_caller]
+    pub fn swap_remove_and_drop(&mut self, index: usize) {
+        self.swap_with_last(index);
+        self.pop_and_drop();
+    }
+
+    #[track_caller]
+    fn swap_with_last(&mut self, index: usize) {
+        let hole = self.get_ptr(index);
+        let last = self.get_ptr(self.len - 1);
+        if hole != last {
+            unsafe { std::ptr::swap_nonoverlapping(hole.as_ptr(), last.as_ptr(), self.item_layout().size()) }
+        }
+    }
+
+    /// Drops every element, the chunks are kept for reuse.
+    /// Every chunk is emptied before anything is dropped, so a panicking destructor leaks the rest
+    /// instead of leaving stale elements behind.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        let forgotten = self.chunks.iter_mut().map(|chunk| chunk.forget_from(0)).collect::<Vec<_>>();
+        for (chunk, len) in self.chunks.iter_mut().zip(forgotten) {
+            unsafe { chunk.drop_range(0, len) }
+        }
+    }
+
+    /// Frees the chunks that don't hold any elements.
+    pub fn shrink_to_fit(&mut self) {
+        self.chunks.truncate(self.len.div_ceil(self.chunk_len))
+    }
+
+    /// The non-empty chunks, as contiguous slices.
+    #[track_caller]
+    pub fn chunks<T: 'static>(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.prototype.assert_type::<T>();
+        let used = self.len.div_ceil(self.chunk_len);
+        self.chunks[..used].iter().map(BlobArray::as_slice)
+    }
+
+    #[track_caller]
+    pub fn chunks_mut<T: 'static>(&mut self) -> impl DoubleEndedIterator<Item = &mut [T]> {
+        self.prototype.assert_type::<T>();
+        let used = self.len.div_ceil(self.chunk_len);
+        self.chunks[..used].iter_mut().map(BlobArray::as_mut_slice)
+    }
+
+    #[track_caller]
+    pub fn iter<T: 'static>(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.chunks::<T>().flatten()
+    }
+
+    #[track_caller]
+    pub fn iter_mut<T: 'static>(&mut self) -> impl DoubleEndedIterator<Item = &mut T> {
+        self.chunks_mut::<T>().flatten()
+    }
+}
+
+impl<A: Allocator + Clone> std::fmt::Debug for ChunkedBlobArray<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkedBlobArray")
+            .field("type_name", &self.type_name())
+            .field("len", &self.len)
+            .field("chunk_len", &self.chunk_len)
+            .field("chunks", &self.chunks.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stable_addresses() {
+        let mut chunked = ChunkedBlobArray::new::<String>(4);
+        chunked.push("first".to_string());
+        let first = chunked.get_ptr(0);
+
+        for i in 1..100 {
+            chunked.push(i.to_string());
+        }
+        assert_eq!(chunked.get_ptr(0), first);
+        assert_eq!(chunked.capacity(), 100);
+        assert_eq!(chunked.locate(9), (2, 1));
+        assert_eq!(chunked.get::<String>(9).map(String::as_str), Some("9"));
+        assert_eq!(chunked.chunks::<String>().count(), 25);
+        assert!(chunked.chunks::<String>().all(|chunk| chunk.len() == 4));
+
+        assert_eq!(chunked.swap_remove::<String>(1).as_deref(), Some("1"));
+        assert_eq!(chunked.get::<String>(1).map(String::as_str), Some("99"));
+        assert_eq!(chunked.pop::<String>().as_deref(), Some("98"));
+        chunked.swap_remove_and_drop(0);
+        assert_eq!(chunked.get::<String>(0).map(String::as_str), Some("97"));
+        assert_eq!(chunked.len(), 97);
+        assert_eq!(unsafe { chunked.get_ptr(0).cast::<String>().as_ref() }, "97");
+
+        for value in chunked.iter_mut::<String>() {
+            value.push('!');
+        }
+        assert_eq!(chunked.iter::<String>().next_back().map(String::as_str), Some("96!"));
+
+        chunked.clear();
+        assert!(chunked.is_empty() && chunked.iter::<String>().next().is_none());
+        chunked.push("again".to_string());
+        chunked.shrink_to_fit();
+        assert_eq!(chunked.capacity(), 4);
+    }
+
+    #[test]
+    fn type_erased() {
+        let id = Some(TypeId::of::<u64>());
+        let mut chunked = unsafe { ChunkedBlobArray::from_layout(Layout::new::<u64>(), None, id, 2) };
+        for value in 0..5u64 {
+            unsafe { chunked.push_raw((&raw const value).cast()) };
+        }
+        assert_eq!(chunked.get::<u64>(4), Some(&4));
+        assert_eq!(chunked.iter::<u64>().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+
+        let mut out = 0u64;
+        unsafe { chunked.swap_remove_raw(1, (&raw mut out).cast()) };
+        assert_eq!(out, 1);
+        assert!(unsafe { chunked.pop_raw((&raw mut out).cast()) });
+        assert_eq!(out, 3);
+        assert_eq!(chunked.iter::<u64>().copied().collect::<Vec<_>>(), [0, 4, 2]);
+        assert!(matches!(chunked.try_push(1u32), Err(BlobArrayError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn clear_panic() {
+        struct Bomb(u32);
+
+        impl Drop for Bomb {
+            fn drop(&mut self) {
+                if self.0 == 2 {
+                    panic!("boom");
+                }
+            }
+        }
+
+        let mut chunked = ChunkedBlobArray::new::<Bomb>(2);
+        for i in 0..6 {
+            chunked.push(Bomb(i));
+        }
+        let stable = chunked.get_ptr(4);
+
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| chunked.clear())).is_err());
+        assert!(chunked.is_empty());
+
+        for i in 10..16 {
+            chunked.push(Bomb(i));
+        }
+        assert_eq!(chunked.get::<Bomb>(4).map(|bomb| bomb.0), Some(14));
+        assert_eq!(chunked.get_ptr(4), stable);
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_type() {
+        let chunked = ChunkedBlobArray::new::<u32>(8);
+        chunked.get::<u64>(0);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{Allocator, BlobArray, BlobArrayError, Global};
+
+impl BlobArray {
+    /// Like [`BlobArray::new`], but also stores a clone fn so the array implements [`Clone`].
+    #[track_caller]
+    pub fn new_clone<T: Clone + 'static>(capacity: usize) -> Self {
+        Self::new_clone_in::<T>(capacity, Global)
+    }
+
+    /// Like [`BlobArray::new_clone`], but clones the elements with a single memcpy.
+    #[track_caller]
+    pub fn new_copy<T: Copy + 'static>(capacity: usize) -> Self {
+        Self::new_copy_in::<T>(capacity, Global)
+    }
+}
+
+impl<A: Allocator> BlobArray<A> {
+    #[track_caller]
+    pub fn new_clone_in<T: Clone + 'static>(capacity: usize, alloc: A) -> Self {
+        let mut this = Self::new_in::<T>(capacity, alloc);
+        this.clone = Some(clone_fn::<T>);
+        this
+    }
+
+    #[track_caller]
+    pub fn new_copy_in<T: Copy + 'static>(capacity: usize, alloc: A) -> Self {
+        let mut this = Self::new_in::<T>(capacity, alloc);
+        this.clone = Some(copy_fn::<T>);
+        this
+    }
+
+    /// Whether the array was created with a clone fn, see [`BlobArray::new_clone`].
+    pub fn is_cloneable(&self) -> bool {
+        self.clone.is_some()
+    }
+
+    /// Clones every element into `dst`, which must have room for `self.len` more elements.
+    /// `dst.len` is only bumped once all of them are cloned.
+    fn clone_into_spare(&self, dst: &mut Self) -> Result<(), BlobArrayError> {
+        let clone = self.clone.ok_or(BlobArrayError::NotCloneable { type_name: self.type_name })?;
+        unsafe {
+            let end = dst.block.as_ptr().add(dst.len * dst.item_layout.size());
+            clone(self.block.as_ptr(), end, self.len);
+        }
+        dst.len += self.len;
+        Ok(())
+    }
+}
+
+impl<A: Allocator + Clone> BlobArray<A> {
+    pub fn try_clone(&self) -> Result<Self, BlobArrayError> {
+        let mut this = self.empty_like(self.len)?;
+        self.clone_into_spare(&mut this)?;
+        this.ticks = self.ticks.clone();
+        Ok(this)
+    }
+}
+
+/// Panics if the array wasn't created with a clone fn.
+impl<A: Allocator + Clone> Clone for BlobArray<A> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.try_clone().unwrap_or_else(|err| err.handle())
+    }
+
+    /// Reuses the existing allocation when both arrays hold the same type.
+    #[track_caller]
+    fn clone_from(&mut self, source: &Self) {
+        if self.type_id.is_none() || self.type_id != source.type_id {
+            return *self = source.clone()
+        }
+
+        self.clear();
+        self.reserve(source.len);
+        source.clone_into_spare(self).unwrap_or_else(|err| err.handle());
+        self.ticks = source.ticks.clone();
+        self.clone = source.clone;
+    }
+}
+
+/// # Safety
+/// `src` must point to `len` values of `T`, and `dst` to room for `len` more.
+unsafe fn clone_fn<T: Clone>(src: *const u8, dst: *mut u8, len: usize) {
+    /// Drops the clones written so far if `T::clone` panics.
+    struct Guard<T> {
+        dst: *mut T,
+        cloned: usize,
+    }
+
+    impl<T> Drop for Guard<T> {
+        fn drop(&mut self) {
+            unsafe { std::ptr::slice_from_raw_parts_mut(self.dst, self.cloned).drop_in_place() }
+        }
+    }
+
+    let src = src.cast::<T>();
+    let mut guard = Guard { dst: dst.cast::<T>(), cloned: 0 };
+    while guard.cloned < len {
+        unsafe {
+            let value = (*src.add(guard.cloned)).clone();
+            guard.dst.add(guard.cloned).write(value);
+        }
+        guard.cloned += 1;
+    }
+    std::mem::forget(guard);
+}
+
+/// # Safety
+/// See [`clone_fn`].
+unsafe fn copy_fn<T: Copy>(src: *const u8, dst: *mut u8, len: usize) {
+    unsafe { std::ptr::copy_nonoverlapping(src.cast::<T>(), dst.cast::<T>(), len) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn clone_and_clone_from() {
+        let mut ba = BlobArray::new_clone::<String>(0);
+        ba.push("Ochi".to_string());
+        ba.push("Nana".to_string());
+
+        let cloned = ba.clone();
+        assert_eq!(cloned.as_slice::<String>(), ba.as_slice::<String>());
+        assert!(cloned.is_cloneable());
+
+        let mut target = BlobArray::new_clone::<String>(16);
+        target.push("old".to_string());
+        target.clone_from(&ba);
+        assert_eq!(target.as_slice::<String>(), ba.as_slice::<String>());
+        assert!(target.capacity() >= 16);
+
+        let mut other = BlobArray::new::<u8>(0);
+        other.clone_from(&ba);
+        assert!(other.is::<String>());
+        assert_eq!(other.len(), 2);
+
+        let mut copies = BlobArray::new_copy::<[u32; 2]>(0);
+        copies.extend_from_iter([[1u32, 2], [3, 4]]);
+        assert_eq!(copies.clone().as_slice::<[u32; 2]>(), &[[1, 2], [3, 4]]);
+
+        let split = ba.split_off(1);
+        assert_eq!(split.clone().as_slice::<String>(), &["Nana".to_string()]);
+    }
+
+    #[test]
+    fn not_cloneable() {
+        let ba = BlobArray::new::<u32>(0);
+        assert!(!ba.is_cloneable());
+        assert_eq!(ba.try_clone().err(), Some(BlobArrayError::NotCloneable { type_name: "u32" }));
+    }
+
+    #[test]
+    fn clone_panic_drops_clones() {
+        struct Bomb {
+            drops: Rc<Cell<usize>>,
+            explode: bool,
+        }
+
+        impl Clone for Bomb {
+            fn clone(&self) -> Self {
+                assert!(!self.explode, "boom");
+                Self { drops: self.drops.clone(), explode: false }
+            }
+        }
+
+        impl Drop for Bomb {
+            fn drop(&mut self) {
+                self.drops.set(self.drops.get() + 1);
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        let mut ba = BlobArray::new_clone::<Bomb>(0);
+        for explode in [false, false, true] {
+            ba.push(Bomb { drops: drops.clone(), explode });
+        }
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| ba.clone()));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+
+        drop(ba);
+        assert_eq!(drops.get(), 5);
+    }
+}
@@ -0,0 +1,286 @@
+use std::io::{self, Read, Write};
+
+use crate::{Allocator, BlobArray, BlobArrayError, Pod};
+
+const MAGIC: [u8; 8] = *b"BLOBARR\0";
+const VERSION: u16 = 1;
+/// The header is padded to 64 bytes, so the element bytes that follow it stay aligned in a mapped file.
+pub(crate) const HEADER_LEN: usize = 64;
+
+/// Where the element count sits in the header, so a mapped file can update it in place.
+pub(crate) const LEN_OFFSET: usize = 32;
+
+/// How many element bytes [`BlobArray::read_from`] reads at a time.
+const READ_CHUNK: usize = 64 * 1024;
+
+const LITTLE_ENDIAN: u8 = 0;
+const BIG_ENDIAN: u8 = 1;
+const NATIVE_ENDIAN: u8 = if cfg!(target_endian = "little") { LITTLE_ENDIAN } else { BIG_ENDIAN };
+
+const HAS_TYPE_HASH: u8 = 1;
+
+#[derive(Debug)]
+pub enum FormatError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u16),
+    /// The elements were written on a machine with the other byte order.
+    EndiannessMismatch,
+    LayoutMismatch { expected: (usize, usize), found: (usize, usize) },
+    TypeHashMismatch { expected: u64, found: Option<u64> },
+    Array(BlobArrayError),
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "BlobArray io error: {err}"),
+            Self::BadMagic => f.write_str("not a BlobArray file"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported BlobArray format version {version}"),
+            Self::EndiannessMismatch => f.write_str("BlobArray file was written with a different endianness"),
+            Self::LayoutMismatch { expected, found } => write!(
+                f,
+                "BlobArray file layout mismatch: expected size {} align {}, found size {} align {}",
+                expected.0, expected.1, found.0, found.1,
+            ),
+            Self::TypeHashMismatch { expected, found: Some(found) } => {
+                write!(f, "BlobArray file type hash mismatch: expected {expected:#x}, found {found:#x}")
+            }
+            Self::TypeHashMismatch { expected, found: None } => {
+                write!(f, "BlobArray file type hash mismatch: expected {expected:#x}, found none")
+            }
+            Self::Array(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Array(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FormatError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<BlobArrayError> for FormatError {
+    fn from(err: BlobArrayError) -> Self {
+        Self::Array(err)
+    }
+}
+
+/// FNV-1a of `name`. Unlike `TypeId`, this stays the same across compilers and builds,
+/// so it can be stored next to the data, e.g. `stable_type_hash("my_game::Position")`.
+pub const fn stable_type_hash(name: &str) -> u64 {
+    let bytes = name.as_bytes();
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header {
+    pub(crate) item_size: usize,
+    pub(crate) align: usize,
+    pub(crate) len: usize,
+    pub(crate) type_hash: Option<u64>,
+}
+
+impl Header {
+    pub(crate) fn new<T: Pod>(len: usize, type_hash: Option<u64>) -> Self {
+        Self {
+            item_size: size_of::<T>(),
+            align: align_of::<T>(),
+            len,
+            type_hash,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0; HEADER_LEN];
+        bytes[0..8].copy_from_slice(&MAGIC);
+        bytes[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[10] = NATIVE_ENDIAN;
+        bytes[11] = if self.type_hash.is_some() { HAS_TYPE_HASH } else { 0 };
+        bytes[16..24].copy_from_slice(&(self.item_size as u64).to_le_bytes());
+        bytes[24..32].copy_from_slice(&(self.align as u64).to_le_bytes());
+        bytes[LEN_OFFSET..LEN_OFFSET + 8].copy_from_slice(&(self.len as u64).to_le_bytes());
+        bytes[40..48].copy_from_slice(&self.type_hash.unwrap_or(0).to_le_bytes());
+        bytes
+    }
+
+    pub(crate) fn decode(bytes: &[u8; HEADER_LEN]) -> Result<Self, FormatError> {
+        if bytes[0..8] != MAGIC {
+            return Err(FormatError::BadMagic)
+        }
+
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion(version))
+        }
+
+        if bytes[10] != NATIVE_ENDIAN {
+            return Err(FormatError::EndiannessMismatch)
+        }
+
+        let read = |at: usize| {
+            let value = u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+            usize::try_from(value).map_err(|_| FormatError::Array(BlobArrayError::CapacityOverflow))
+        };
+
+        Ok(Self {
+            item_size: read(16)?,
+            align: read(24)?,
+            len: read(LEN_OFFSET)?,
+            type_hash: (bytes[11] & HAS_TYPE_HASH != 0)
+                .then(|| u64::from_le_bytes(bytes[40..48].try_into().unwrap())),
+        })
+    }
+
+    /// Checks that the stored elements can be read back as `T`.
+    pub(crate) fn validate<T: Pod>(&self, type_hash: Option<u64>) -> Result<(), FormatError> {
+        let expected = (size_of::<T>(), align_of::<T>());
+        if (self.item_size, self.align) != expected {
+            return Err(FormatError::LayoutMismatch { expected, found: (self.item_size, self.align) })
+        }
+
+        if let Some(expected) = type_hash && self.type_hash != Some(expected) {
+            return Err(FormatError::TypeHashMismatch { expected, found: self.type_hash })
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn data_len(&self) -> Result<usize, FormatError> {
+        self.len.checked_mul(self.item_size).ok_or(FormatError::Array(BlobArrayError::CapacityOverflow))
+    }
+}
+
+impl<A: Allocator> BlobArray<A> {
+    /// Writes a versioned header followed by the raw element bytes.
+    /// `type_hash` is stored as is, see [`stable_type_hash`].
+    #[track_caller]
+    pub fn write_to<T: Pod>(&self, mut writer: impl Write, type_hash: Option<u64>) -> Result<(), FormatError> {
+        let bytes = self.as_bytes::<T>();
+        writer.write_all(&Header::new::<T>(self.len, type_hash).encode())?;
+        writer.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+impl BlobArray {
+    /// Reads an array written by [`BlobArray::write_to`].
+    /// If `type_hash` is given, the file must have been written with the same one.
+    pub fn read_from<T: Pod>(mut reader: impl Read, type_hash: Option<u64>) -> Result<Self, FormatError> {
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        let header = Header::decode(&header)?;
+        header.validate::<T>(type_hash)?;
+
+        let mut remaining = header.data_len()?;
+        let mut array = BlobArray::try_new::<T>(0)?;
+        if size_of::<T>() == 0 {
+            array.len = header.len;
+            return Ok(array)
+        }
+
+        // the length is untrusted, so the data is read in bounded chunks and the array grows as bytes arrive
+        let chunk_len = (READ_CHUNK / size_of::<T>()).max(1) * size_of::<T>();
+        let mut chunk = vec![0; chunk_len.min(remaining)];
+        while remaining > 0 {
+            let bytes = &mut chunk[..chunk_len.min(remaining)];
+            reader.read_exact(bytes)?;
+            array.try_extend_from_bytes::<T>(bytes)?;
+            remaining -= bytes.len();
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const POSITION: u64 = stable_type_hash("Position");
+
+    fn positions() -> BlobArray {
+        let mut ba = BlobArray::new::<[f32; 3]>(0);
+        ba.extend_from_iter((0..100).map(|i| [i as f32, 0.5, -1.0]));
+        ba
+    }
+
+    #[test]
+    fn round_trip() {
+        let ba = positions();
+        let mut file = Vec::new();
+        ba.write_to::<[f32; 3]>(&mut file, Some(POSITION)).unwrap();
+        assert_eq!(file.len(), HEADER_LEN + 100 * 12);
+
+        let back = BlobArray::read_from::<[f32; 3]>(file.as_slice(), Some(POSITION)).unwrap();
+        assert_eq!(back.as_slice::<[f32; 3]>(), ba.as_slice::<[f32; 3]>());
+
+        let back = BlobArray::read_from::<[f32; 3]>(file.as_slice(), None).unwrap();
+        assert_eq!(back.len(), 100);
+
+        let empty = BlobArray::new::<u64>(0);
+        let mut file = Vec::new();
+        empty.write_to::<u64>(&mut file, None).unwrap();
+        assert!(BlobArray::read_from::<u64>(file.as_slice(), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn validation() {
+        let mut file = Vec::new();
+        positions().write_to::<[f32; 3]>(&mut file, Some(POSITION)).unwrap();
+
+        assert!(matches!(
+            BlobArray::read_from::<[f32; 4]>(file.as_slice(), None),
+            Err(FormatError::LayoutMismatch { expected: (16, 4), found: (12, 4) }),
+        ));
+        assert!(matches!(
+            BlobArray::read_from::<[f32; 3]>(file.as_slice(), Some(stable_type_hash("Velocity"))),
+            Err(FormatError::TypeHashMismatch { found: Some(POSITION), .. }),
+        ));
+        assert!(matches!(
+            BlobArray::read_from::<[f32; 3]>(&file[..file.len() - 1], None),
+            Err(FormatError::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof,
+        ));
+
+        let mut bad = file.clone();
+        bad[0] = b'X';
+        assert!(matches!(BlobArray::read_from::<[f32; 3]>(bad.as_slice(), None), Err(FormatError::BadMagic)));
+
+        let mut bad = file.clone();
+        bad[8] = 2;
+        assert!(matches!(
+            BlobArray::read_from::<[f32; 3]>(bad.as_slice(), None),
+            Err(FormatError::UnsupportedVersion(2)),
+        ));
+
+        let mut huge = file.clone();
+        huge[LEN_OFFSET..LEN_OFFSET + 8].copy_from_slice(&(u64::MAX / 64).to_le_bytes());
+        assert!(matches!(
+            BlobArray::read_from::<[f32; 3]>(huge.as_slice(), None),
+            Err(FormatError::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof,
+        ));
+
+        let mut bad = file;
+        bad[10] ^= 1;
+        assert!(matches!(
+            BlobArray::read_from::<[f32; 3]>(bad.as_slice(), None),
+            Err(FormatError::EndiannessMismatch),
+        ));
+    }
+}
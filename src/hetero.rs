@@ -0,0 +1,394 @@
+use std::alloc;
+use std::any::TypeId;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::{dangling, drop_fn, BlobArrayError};
+
+struct ElementInfo {
+    offset: usize,
+    layout: alloc::Layout,
+    type_id: TypeId,
+    type_name: &'static str,
+    drop: Option<unsafe fn(*mut u8)>,
+}
+
+/// Stores values of different types packed into a single aligned allocation, unlike `Vec<Box<dyn Any>>`
+/// which needs one allocation per value. Each value keeps its own offset, layout, type and drop fn in a side table.
+pub struct HeteroBlobArray {
+    block: NonNull<u8>,
+    len_bytes: usize,
+    capacity_bytes: usize,
+    align: usize,
+    infos: Vec<ElementInfo>,
+}
+
+impl Drop for HeteroBlobArray {
+    fn drop(&mut self) {
+        self.clear();
+
+        if self.capacity_bytes > 0 {
+            unsafe { alloc::dealloc(self.block.as_ptr(), self.block_layout()) }
+        }
+    }
+}
+
+impl Default for HeteroBlobArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeteroBlobArray {
+    pub fn new() -> Self {
+        Self {
+            block: dangling(1),
+            len_bytes: 0,
+            capacity_bytes: 0,
+            align: 1,
+            infos: Vec::new(),
+        }
+    }
+
+    #[track_caller]
+    pub fn with_capacity(len: usize, bytes: usize) -> Self {
+        let mut this = Self::new();
+        this.infos.reserve(len);
+        this.try_realloc(bytes, 1).unwrap_or_else(|err| err.handle());
+        this
+    }
+
+    pub fn len(&self) -> usize {
+        self.infos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.infos.is_empty()
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.len_bytes
+    }
+
+    pub fn capacity_bytes(&self) -> usize {
+        self.capacity_bytes
+    }
+
+    fn block_layout(&self) -> alloc::Layout {
+        unsafe { alloc::Layout::from_size_align_unchecked(self.capacity_bytes, self.align) }
+    }
+
+    #[track_caller]
+    pub fn push<T: 'static>(&mut self, value: T) {
+        self.try_push(value).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_push<T: 'static>(&mut self, value: T) -> Result<(), BlobArrayError> {
+        let layout = alloc::Layout::new::<T>();
+        let offset = self.try_reserve_for(layout)?;
+
+        unsafe { self.block.add(offset).cast::<T>().write(value) }
+
+        self.len_bytes = offset + layout.size();
+        self.infos.push(ElementInfo {
+            offset,
+            layout,
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            drop: drop_fn::<T>(),
+        });
+
+        Ok(())
+    }
+
+    /// Makes room for a value of `layout` and returns the offset it has to be written at.
+    fn try_reserve_for(&mut self, layout: alloc::Layout) -> Result<usize, BlobArrayError> {
+        let offset = self.len_bytes
+            .checked_next_multiple_of(layout.align())
+            .ok_or(BlobArrayError::CapacityOverflow)?;
+        let required = offset
+            .checked_add(layout.size())
+            .ok_or(BlobArrayError::CapacityOverflow)?;
+
+        if required > self.capacity_bytes || layout.align() > self.align {
+            let new_capacity = if required > self.capacity_bytes {
+                self.capacity_bytes.saturating_mul(2).max(required).max(64)
+            } else {
+                self.capacity_bytes
+            };
+            self.try_realloc(new_capacity, layout.align())?;
+        }
+
+        self.infos.try_reserve(1).map_err(|_| BlobArrayError::CapacityOverflow)?;
+
+        Ok(offset)
+    }
+
+    fn try_realloc(&mut self, new_capacity: usize, align: usize) -> Result<(), BlobArrayError> {
+        let align = self.align.max(align);
+        let new_layout = alloc::Layout::from_size_align(new_capacity, align)
+            .map_err(|_| BlobArrayError::CapacityOverflow)?;
+
+        if new_layout.size() == 0 {
+            self.align = align;
+            self.block = dangling(align);
+            return Ok(())
+        }
+
+        let raw = unsafe {
+            if self.capacity_bytes == 0 {
+                alloc::alloc(new_layout)
+            } else if align == self.align {
+                alloc::realloc(self.block.as_ptr(), self.block_layout(), new_capacity)
+            } else {
+                // offsets stay valid, the new block just has to be aligned for the strictest value
+                let raw = alloc::alloc(new_layout);
+                if !raw.is_null() {
+                    std::ptr::copy_nonoverlapping(self.block.as_ptr(), raw, self.len_bytes);
+                    alloc::dealloc(self.block.as_ptr(), self.block_layout());
+                }
+                raw
+            }
+        };
+
+        self.block = NonNull::new(raw).ok_or(BlobArrayError::AllocFailed { layout: new_layout })?;
+        self.capacity_bytes = new_capacity;
+        self.align = align;
+
+        Ok(())
+    }
+
+    pub fn type_id(&self, index: usize) -> Option<TypeId> {
+        self.infos.get(index).map(|info| info.type_id)
+    }
+
+    pub fn type_name(&self, index: usize) -> Option<&'static str> {
+        self.infos.get(index).map(|info| info.type_name)
+    }
+
+    pub fn is<T: 'static>(&self, index: usize) -> bool {
+        self.type_id(index) == Some(TypeId::of::<T>())
+    }
+
+    /// Returns the value at `index` if there is one and it is a `T`.
+    pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.get_ref(index)?.downcast_ref()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        if !self.is::<T>(index) { return None }
+
+        unsafe { Some(self.block.add(self.infos[index].offset).cast::<T>().as_mut()) }
+    }
+
+    pub fn get_ref(&self, index: usize) -> Option<HeteroRef<'_>> {
+        self.infos.get(index).map(|info| HeteroRef::new(self, info))
+    }
+
+    /// Moves the value at `value` into the array.
+    ///
+    /// # Safety
+    /// `value` must point to a valid value described by `layout` and `drop`, which the array takes ownership of.
+    pub(crate) unsafe fn push_erased(
+        &mut self,
+        type_id: TypeId,
+        type_name: &'static str,
+        layout: alloc::Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        value: *const u8,
+    ) {
+        let offset = self.try_reserve_for(layout).unwrap_or_else(|err| err.handle());
+
+        unsafe { std::ptr::copy_nonoverlapping(value, self.block.add(offset).as_ptr(), layout.size()) }
+
+        self.len_bytes = offset + layout.size();
+        self.infos.push(ElementInfo { offset, layout, type_id, type_name, drop });
+    }
+
+    /// Hands every value to `f` to be moved out, then forgets them without dropping.
+    pub(crate) unsafe fn take_all(&mut self, mut f: impl FnMut(TypeId, NonNull<u8>)) {
+        for info in self.infos.drain(..) {
+            f(info.type_id, unsafe { self.block.add(info.offset) });
+        }
+        self.len_bytes = 0;
+    }
+
+    /// Removes the last value if it is a `T`.
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        if !self.is::<T>(self.len().checked_sub(1)?) { return None }
+
+        let info = self.infos.pop()?;
+        self.len_bytes = info.offset;
+        unsafe { Some(self.block.add(info.offset).cast::<T>().read()) }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        while self.infos.len() > len {
+            let info = self.infos.pop().unwrap();
+            self.len_bytes = info.offset;
+            if let Some(drop) = info.drop {
+                unsafe { drop(self.block.add(info.offset).as_ptr()) }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Iterates over every value along with its type information.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = HeteroRef<'_>> + ExactSizeIterator {
+        self.infos.iter().map(|info| HeteroRef::new(self, info))
+    }
+
+    /// Iterates over the values which are a `T`, skipping everything else.
+    pub fn downcast<T: 'static>(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.iter().filter_map(|element| element.downcast_ref())
+    }
+
+    pub fn downcast_mut<T: 'static>(&mut self) -> impl DoubleEndedIterator<Item = &mut T> {
+        let block = self.block;
+        self.infos
+            .iter()
+            .filter(|info| info.type_id == TypeId::of::<T>())
+            .map(move |info| unsafe { block.add(info.offset).cast::<T>().as_mut() })
+    }
+
+}
+
+impl std::fmt::Debug for HeteroBlobArray {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.infos.iter().map(|info| info.type_name))
+            .finish()
+    }
+}
+
+/// Borrowed, type erased value stored in a [`HeteroBlobArray`].
+#[derive(Clone, Copy)]
+pub struct HeteroRef<'a> {
+    ptr: NonNull<u8>,
+    layout: alloc::Layout,
+    type_id: TypeId,
+    type_name: &'static str,
+    marker: PhantomData<&'a HeteroBlobArray>,
+}
+
+impl<'a> HeteroRef<'a> {
+    fn new(source: &'a HeteroBlobArray, info: &ElementInfo) -> Self {
+        Self {
+            ptr: unsafe { source.block.add(info.offset) },
+            layout: info.layout,
+            type_id: info.type_id,
+            type_name: info.type_name,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn layout(&self) -> alloc::Layout {
+        self.layout
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    pub fn downcast_ref<T: 'static>(self) -> Option<&'a T> {
+        self.is::<T>().then(|| unsafe { self.ptr.cast::<T>().as_ref() })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    #[repr(align(32))]
+    #[derive(Debug, PartialEq)]
+    struct Aligned(u8);
+
+    #[test]
+    fn push_and_get() {
+        let mut hba = HeteroBlobArray::new();
+        hba.push(1u8);
+        hba.push("two".to_string());
+        hba.push(3u64);
+        hba.push(Aligned(4));
+        hba.push(());
+
+        assert_eq!(hba.len(), 5);
+        assert_eq!(hba.get::<u8>(0), Some(&1));
+        assert_eq!(hba.get::<String>(1).map(String::as_str), Some("two"));
+        assert_eq!(hba.get::<u32>(2), None);
+        assert_eq!(hba.get::<Aligned>(3), Some(&Aligned(4)));
+        assert_eq!(hba.get::<()>(4), Some(&()));
+        assert_eq!(hba.get::<u8>(5), None);
+
+        for element in hba.iter() {
+            assert_eq!(element.as_ptr() as usize % element.layout().align(), 0);
+        }
+
+        *hba.get_mut::<u64>(2).unwrap() += 1;
+        assert_eq!(hba.pop::<u64>(), None);
+        assert_eq!(hba.pop::<()>(), Some(()));
+        hba.pop::<Aligned>();
+        assert_eq!(hba.pop::<u64>(), Some(4));
+    }
+
+    #[test]
+    fn downcast_and_drop() {
+        let counter = Rc::new(());
+        let mut hba = HeteroBlobArray::new();
+        for i in 0..10u32 {
+            hba.push(i);
+            hba.push(counter.clone());
+        }
+        assert_eq!(Rc::strong_count(&counter), 11);
+
+        hba.downcast_mut::<u32>().for_each(|value| *value *= 2);
+        assert_eq!(hba.downcast::<u32>().sum::<u32>(), 90);
+        assert_eq!(hba.downcast::<Rc<()>>().count(), 10);
+
+        hba.truncate(5);
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        drop(hba);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn speed() {
+        struct NewObj {
+            _name: String,
+            _age: usize,
+        }
+
+        const NUM: usize = 1024 * 1024;
+
+        let mut hba = HeteroBlobArray::with_capacity(NUM, NUM * size_of::<NewObj>());
+        let now = std::time::Instant::now();
+        for i in 0..NUM {
+            hba.push(NewObj { _name: i.to_string(), _age: i });
+        }
+        println!("hetero blob array push time for {NUM} objects: {:?}", now.elapsed());
+
+        let mut vec: Vec<Box<dyn std::any::Any>> = Vec::with_capacity(NUM);
+        let now = std::time::Instant::now();
+        for i in 0..NUM {
+            vec.push(Box::new(NewObj { _name: i.to_string(), _age: i }));
+        }
+        println!("vec push time for {NUM} objects: {:?}", now.elapsed());
+    }
+}
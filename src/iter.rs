@@ -0,0 +1,375 @@
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use crate::ticks::ChangeTicks;
+use crate::{Allocator, BlobArray, Global};
+
+pub struct Iter<'a, T> {
+    inner: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn new(slice: &'a [T]) -> Self {
+        Self { inner: slice.iter() }
+    }
+
+    pub fn as_slice(&self) -> &'a [T] {
+        self.inner.as_slice()
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n)
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Mutable iterator of a [`BlobArray`], which marks every element it yields as changed if the array tracks changes.
+pub struct IterMut<'a, T> {
+    inner: std::slice::IterMut<'a, T>,
+    ticks: Option<(std::slice::IterMut<'a, u32>, u32)>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(crate) fn new(slice: &'a mut [T], ticks: Option<(std::slice::IterMut<'a, u32>, u32)>) -> Self {
+        Self {
+            inner: slice.iter_mut(),
+            ticks,
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next()?;
+        if let Some((ticks, current)) = &mut self.ticks
+            && let Some(tick) = ticks.next()
+        {
+            *tick = *current;
+        }
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let value = self.inner.nth(n)?;
+        if let Some((ticks, current)) = &mut self.ticks
+            && let Some(tick) = ticks.nth(n)
+        {
+            *tick = *current;
+        }
+        Some(value)
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next_back()?;
+        if let Some((ticks, current)) = &mut self.ticks
+            && let Some(tick) = ticks.next_back()
+        {
+            *tick = *current;
+        }
+        Some(value)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// Consuming iterator of a [`BlobArray`]. Elements which weren't yielded are dropped along with it.
+pub struct IntoIter<T, A: Allocator = Global> {
+    source: BlobArray<A>,
+    front: usize,
+    back: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub(crate) fn new(mut source: BlobArray<A>) -> Self {
+        let back = std::mem::replace(&mut source.len, 0);
+        Self {
+            source,
+            front: 0,
+            back,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            let ptr = self.source.block.as_ptr().cast::<T>().add(self.front);
+            std::slice::from_raw_parts(ptr, self.back - self.front)
+        }
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back { return None }
+
+        unsafe {
+            let value = self.source.block.as_ptr().cast::<T>().add(self.front).read();
+            self.front += 1;
+            Some(value)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back { return None }
+
+        unsafe {
+            self.back -= 1;
+            Some(self.source.block.as_ptr().cast::<T>().add(self.back).read())
+        }
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        let remaining: *mut [T] = self.as_slice() as *const [T] as *mut [T];
+        self.front = self.back;
+        unsafe { std::ptr::drop_in_place(remaining) }
+    }
+}
+
+/// Draining iterator returned by [`BlobArray::drain`].
+pub struct Drain<'a, T, A: Allocator = Global> {
+    source: &'a mut BlobArray<A>,
+    front: usize,
+    back: usize,
+    tail_start: usize,
+    tail_len: usize,
+    tail_ticks: Option<ChangeTicks>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T, A: Allocator> Drain<'a, T, A> {
+    pub(crate) fn new(source: &'a mut BlobArray<A>, range: Range<usize>) -> Self {
+        let tail_len = source.len - range.end;
+        source.len = range.start;
+
+        // the ticks are cut back along with `len`, so leaking the drain leaves them in sync
+        let tail_ticks = source.ticks.as_deref_mut().map(|ticks| {
+            let tail = ticks.split_off(range.end);
+            ticks.truncate(range.start);
+            tail
+        });
+
+        Self {
+            source,
+            front: range.start,
+            back: range.end,
+            tail_start: range.end,
+            tail_len,
+            tail_ticks,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            let ptr = self.source.block.as_ptr().cast::<T>().add(self.front);
+            std::slice::from_raw_parts(ptr, self.back - self.front)
+        }
+    }
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back { return None }
+
+        unsafe {
+            let value = self.source.block.as_ptr().cast::<T>().add(self.front).read();
+            self.front += 1;
+            Some(value)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back { return None }
+
+        unsafe {
+            self.back -= 1;
+            Some(self.source.block.as_ptr().cast::<T>().add(self.back).read())
+        }
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        struct MoveTail<'r, 'a, T, A: Allocator>(&'r mut Drain<'a, T, A>);
+
+        impl<T, A: Allocator> Drop for MoveTail<'_, '_, T, A> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let source = &mut *drain.source;
+
+                unsafe {
+                    let base = source.block.as_ptr().cast::<T>();
+                    if drain.tail_start != source.len {
+                        std::ptr::copy(base.add(drain.tail_start), base.add(source.len), drain.tail_len);
+                    }
+                }
+
+                source.len += drain.tail_len;
+                if let Some(ticks) = source.ticks.as_deref_mut() {
+                    ticks.append(drain.tail_ticks.as_mut(), drain.tail_len);
+                }
+            }
+        }
+
+        let remaining: *mut [T] = self.as_slice() as *const [T] as *mut [T];
+        self.front = self.back;
+
+        let _guard = MoveTail(self);
+        unsafe { std::ptr::drop_in_place(remaining) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    fn counted(len: usize) -> (BlobArray, Rc<()>) {
+        let counter = Rc::new(());
+        let mut ba = BlobArray::new::<Rc<()>>(len);
+        for _ in 0..len {
+            ba.push(counter.clone());
+        }
+        (ba, counter)
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut ba = BlobArray::new::<u32>(0);
+        for i in 0..6u32 {
+            ba.push(i);
+        }
+
+        let mut iter = ba.iter::<u32>();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.len(), 4);
+
+        ba.iter_mut::<u32>().rev().for_each(|value| *value *= 10);
+        assert_eq!(ba.as_slice::<u32>(), &[0, 10, 20, 30, 40, 50]);
+
+        let mut iter = ba.into_iter::<u32>();
+        assert_eq!(iter.next_back(), Some(50));
+        assert_eq!(iter.collect::<Vec<_>>(), [0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn into_iter_drops_rest() {
+        let (ba, counter) = counted(5);
+
+        let mut iter = ba.into_iter::<Rc<()>>();
+        drop(iter.next());
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn drain() {
+        let mut ba = BlobArray::new::<String>(0);
+        for i in 0..6 {
+            ba.push(i.to_string());
+        }
+
+        let drained = ba.drain::<String>(1..4).collect::<Vec<_>>();
+        assert_eq!(drained, ["1", "2", "3"]);
+        assert_eq!(ba.as_slice::<String>(), ["0", "4", "5"]);
+
+        let (mut ba, counter) = counted(6);
+        let mut drain = ba.drain::<Rc<()>>(2..);
+        drop(drain.next());
+        drop(drain);
+        assert_eq!(ba.len(), 2);
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        let (mut ba, counter) = counted(6);
+        let mut drain = ba.drain::<Rc<()>>(1..3);
+        drop(drain.next_back());
+        drop(drain);
+        assert_eq!(ba.len(), 4);
+        assert_eq!(Rc::strong_count(&counter), 5);
+    }
+}
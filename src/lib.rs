@@ -1,95 +1,444 @@
 use std::alloc;
+use std::any::TypeId;
 use std::mem;
 use std::ptr::NonNull;
-use std::num::NonZeroUsize;
 use std::cell::UnsafeCell;
-use std::marker::PhantomData;
+use std::ops::{Bound, Range, RangeBounds};
+
+use ticks::ChangeTicks;
+
+mod allocator;
+mod chunked;
+mod clone;
+#[cfg(feature = "bytemuck")]
+mod format;
+mod hetero;
+mod iter;
+#[cfg(all(feature = "mmap", any(target_os = "linux", target_os = "macos"), target_pointer_width = "64"))]
+mod mmap;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod send;
+#[cfg(feature = "serde")]
+mod serialize;
+mod shared;
+mod slot;
+mod small;
+mod sparse;
+mod table;
+mod ticks;
+mod typed;
+
+pub use allocator::{AllocError, Allocator, Global};
+pub use chunked::ChunkedBlobArray;
+#[cfg(feature = "derive")]
+pub use blob_array_derive::BlobSoa;
+#[cfg(feature = "bytemuck")]
+pub use format::{FormatError, stable_type_hash};
+pub use hetero::{HeteroBlobArray, HeteroRef};
+pub use iter::{Drain, IntoIter, Iter, IterMut};
+#[cfg(all(feature = "mmap", any(target_os = "linux", target_os = "macos"), target_pointer_width = "64"))]
+pub use mmap::MmapBlobArray;
+#[cfg(feature = "rayon")]
+pub use parallel::{Filter, Map, ParChunksMut, ParIter, ParIterMut, ParallelIterator};
+#[cfg(feature = "bytemuck")]
+pub use pod::Pod;
+pub use send::SendBlobArray;
+#[cfg(feature = "serde")]
+pub use serialize::{DeserializeOwned, Serialize, SerializeRegistry};
+pub use shared::{BlobRef, BlobRefMut, SharedBlobArray};
+pub use slot::{Handle, SlotBlobArray};
+pub use small::SmallBlobArray;
+pub use sparse::BlobSparseSet;
+pub use table::BlobTable;
+pub use ticks::CellMut;
+pub use typed::{TypedBlobArray, TypedBlobArrayMut};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobArrayError {
+    TypeMismatch { expected: &'static str, found: &'static str },
+    CapacityOverflow,
+    AllocFailed { layout: alloc::Layout },
+    IndexOutOfBounds { index: usize, len: usize },
+    LayoutMismatch { expected: alloc::Layout, found: alloc::Layout },
+    ColumnMismatch,
+    BorrowConflict,
+    ByteLength { len: usize, item_size: usize },
+    NotCloneable { type_name: &'static str },
+}
+
+impl std::fmt::Display for BlobArrayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "BlobArray type mismatch: storing `{expected}`, accessed as `{found}`")
+            }
+            Self::CapacityOverflow => f.write_str("BlobArray capacity overflow"),
+            Self::AllocFailed { layout } => {
+                write!(f, "BlobArray failed to allocate {} bytes aligned to {}", layout.size(), layout.align())
+            }
+            Self::IndexOutOfBounds { index, len } => {
+                write!(f, "BlobArray index out of bounds: the len is {len} but the index is {index}")
+            }
+            Self::LayoutMismatch { expected, found } => write!(
+                f,
+                "BlobArray layout mismatch: storing size {} align {}, accessed with size {} align {}",
+                expected.size(),
+                expected.align(),
+                found.size(),
+                found.align(),
+            ),
+            Self::ColumnMismatch => f.write_str("BlobTable row doesn't match the table's columns"),
+            Self::BorrowConflict => f.write_str("BlobArray is already borrowed in a conflicting way"),
+            Self::ByteLength { len, item_size } => {
+                write!(f, "BlobArray byte length {len} is not a multiple of the item size {item_size}")
+            }
+            Self::NotCloneable { type_name } => {
+                write!(f, "BlobArray of `{type_name}` was not created with a clone fn")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlobArrayError {}
+
+impl BlobArrayError {
+    #[cold]
+    #[track_caller]
+    fn handle(self) -> ! {
+        match self {
+            Self::AllocFailed { layout } => alloc::handle_alloc_error(layout),
+            err => panic!("{err}"),
+        }
+    }
+}
 
 /// Type erased data storage. This is slightly slower than normal `Vec<T>`,
 /// but faster than `Vec<Box<dyn Any>>` and the data are guaranteed to be stored contiguously.
-/// However, this has double the size (48) compared to a normal Vec (24) which comes from the need to carry additional informations.
-pub struct BlobArray {
+/// However, this is a lot bigger than a normal Vec (24) which comes from the need to carry additional informations.
+/// Typed accessors check `T` against the type the array was created with and panic on mismatch,
+/// the `try_*` variants report it as a [`BlobArrayError`] instead.
+/// Memory comes from `A`, which is the global allocator unless one is passed to a `*_in` constructor.
+pub struct BlobArray<A: Allocator = Global> {
     block: NonNull<u8>,
     len: usize,
-    capacity: NonZeroUsize,
+    capacity: usize,
     item_layout: alloc::Layout,
-    drop: Option<unsafe fn(*mut u8, usize)>,
+    drop: Option<unsafe fn(*mut u8)>,
+    clone: Option<unsafe fn(*const u8, *mut u8, usize)>,
+    type_id: Option<TypeId>,
+    type_name: &'static str,
+    ticks: Option<Box<ChangeTicks>>,
+    send: bool,
+    sync: bool,
+    alloc: A,
 }
 
-impl Drop for BlobArray {
+impl<A: Allocator> Drop for BlobArray<A> {
     fn drop(&mut self) {
-        unsafe {
-            self.clear();
-            let size = self.item_layout.size() * self.capacity.get();
-            let align = self.item_layout.align();
-            let layout = alloc::Layout::from_size_align_unchecked(size, align);
-            alloc::dealloc(self.block.as_ptr(), layout);
+        self.clear();
+
+        if let Ok(layout) = self.array_layout(self.capacity) && layout.size() > 0 {
+            unsafe { self.alloc.deallocate(self.block, layout) }
         }
     }
 }
 
 impl BlobArray {
-    pub fn new<T>(capacity: usize) -> Self {
-        #[inline]
-        unsafe fn drop<T>(raw: *mut u8, len: usize) {
-            unsafe {
-                let ptr = raw.cast::<T>();
-                for i in 0..len {
-                    let to_drop = ptr.add(i);
-                    std::ptr::drop_in_place(to_drop);
-                }
-            }
-        }
+    #[track_caller]
+    pub fn new<T: 'static>(capacity: usize) -> Self {
+        Self::new_in::<T>(capacity, Global)
+    }
+
+    pub fn try_new<T: 'static>(capacity: usize) -> Result<Self, BlobArrayError> {
+        Self::try_new_in::<T>(capacity, Global)
+    }
+
+    /// Creates an array whose element type is only known at runtime, through its `layout`, `drop` fn and `type_id`.
+    /// Typed accessors only accept the type `type_id` stands for, without one the array can only be used
+    /// through the raw methods.
+    ///
+    /// # Safety
+    /// `drop` must be sound to call on every element stored in the array.
+    /// If `type_id` is given, `layout` and `drop` must be those of the type it stands for.
+    #[track_caller]
+    pub unsafe fn from_layout(
+        layout: alloc::Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        type_id: Option<TypeId>,
+        capacity: usize,
+    ) -> Self {
+        unsafe { Self::from_layout_in(layout, drop, type_id, capacity, Global) }
+    }
+
+    /// # Safety
+    /// See [`BlobArray::from_layout`].
+    pub unsafe fn try_from_layout(
+        layout: alloc::Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        type_id: Option<TypeId>,
+        capacity: usize,
+    ) -> Result<Self, BlobArrayError> {
+        unsafe { Self::try_from_layout_in(layout, drop, type_id, capacity, Global) }
+    }
+}
+
+impl<A: Allocator> BlobArray<A> {
+    #[track_caller]
+    pub fn new_in<T: 'static>(capacity: usize, alloc: A) -> Self {
+        Self::try_new_in::<T>(capacity, alloc).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_new_in<T: 'static>(capacity: usize, alloc: A) -> Result<Self, BlobArrayError> {
+        let mut this = Self::erased(alloc::Layout::new::<T>(), drop_fn::<T>(), alloc);
+        this.type_id = Some(TypeId::of::<T>());
+        this.type_name = std::any::type_name::<T>();
 
-        let capacity = NonZeroUsize::try_from(capacity).unwrap();
-        let size = size_of::<T>();
-        let align = align_of::<T>();
+        this.try_realloc(capacity)?;
+        Ok(this)
+    }
 
+    /// # Safety
+    /// See [`BlobArray::from_layout`].
+    #[track_caller]
+    pub unsafe fn from_layout_in(
+        layout: alloc::Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        type_id: Option<TypeId>,
+        capacity: usize,
+        alloc: A,
+    ) -> Self {
         unsafe {
-            let layout = alloc::Layout::from_size_align_unchecked(size * capacity.get(), align);
-            let raw = std::alloc::alloc(layout);
+            Self::try_from_layout_in(layout, drop, type_id, capacity, alloc).unwrap_or_else(|err| err.handle())
+        }
+    }
 
-            if raw.is_null() {
-                alloc::handle_alloc_error(layout);
-            }
+    /// # Safety
+    /// See [`BlobArray::from_layout`].
+    pub unsafe fn try_from_layout_in(
+        layout: alloc::Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+        type_id: Option<TypeId>,
+        capacity: usize,
+        alloc: A,
+    ) -> Result<Self, BlobArrayError> {
+        let mut this = Self::erased(layout.pad_to_align(), drop, alloc);
+        this.type_id = type_id;
+        this.try_realloc(capacity)?;
+        Ok(this)
+    }
 
-            Self {
-                block: NonNull::new_unchecked(raw),
-                len: 0,
-                capacity,
-                item_layout: alloc::Layout::from_size_align_unchecked(size, align),
-                drop: mem::needs_drop::<T>().then_some(drop::<T>),
-            }
+    fn erased(item_layout: alloc::Layout, drop: Option<unsafe fn(*mut u8)>, alloc: A) -> Self {
+        Self {
+            block: dangling(item_layout.align()),
+            len: 0,
+            // zero sized elements never touch the allocator, so there's no limit other than `len` itself
+            capacity: if item_layout.size() == 0 { usize::MAX } else { 0 },
+            item_layout,
+            drop,
+            clone: None,
+            type_id: None,
+            type_name: "<type erased>",
+            ticks: None,
+            send: false,
+            sync: false,
+            alloc,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn item_layout(&self) -> alloc::Layout {
+        self.item_layout
+    }
+
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.type_id
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == Some(TypeId::of::<T>())
+    }
+
+    #[inline(always)]
+    fn check_type<T: 'static>(&self) -> Result<(), BlobArrayError> {
+        match self.type_id {
+            Some(type_id) if type_id == TypeId::of::<T>() => Ok(()),
+            Some(_) => Err(BlobArrayError::TypeMismatch {
+                expected: self.type_name,
+                found: std::any::type_name::<T>(),
+            }),
+            // a matching layout says nothing about the stored bytes being valid `T`s
+            None => Err(BlobArrayError::TypeMismatch {
+                expected: self.type_name,
+                found: std::any::type_name::<T>(),
+            }),
         }
     }
 
-    pub fn push<T>(&mut self, data: T) {
-        let size = size_of::<T>();
-        let align = align_of::<T>();
-        let capacity = self.capacity.get();
+    #[inline(always)]
+    #[track_caller]
+    fn assert_type<T: 'static>(&self) {
+        if let Err(err) = self.check_type::<T>() {
+            err.handle()
+        }
+    }
 
-        if self.len == capacity {
-            self.realloc(capacity + 1);
+    #[inline(always)]
+    fn check_index(&self, index: usize) -> Result<(), BlobArrayError> {
+        if index < self.len {
+            Ok(())
+        } else {
+            Err(BlobArrayError::IndexOutOfBounds { index, len: self.len })
+        }
+    }
+
+    #[track_caller]
+    pub fn push<T: 'static>(&mut self, data: T) {
+        self.try_push(data).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_push<T: 'static>(&mut self, data: T) -> Result<(), BlobArrayError> {
+        self.check_type::<T>()?;
+        unsafe { self.try_push_unchecked(data) }
+    }
+
+    unsafe fn try_push_unchecked<T>(&mut self, data: T) -> Result<(), BlobArrayError> {
+        if self.len == self.capacity {
+            self.try_reserve(1)?;
         }
 
         unsafe {
-            let raw = self.block.add(self.len * size);
-            let aligned = raw.align_offset(align);
-            let ptr = raw.add(aligned).as_ptr();
-            std::ptr::write(ptr.cast::<T>(), data);
+            let raw = self.block.add(self.len * size_of::<T>());
+            std::ptr::write(raw.as_ptr().cast::<T>(), data);
         }
 
         self.len += 1;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.push();
+        }
+        Ok(())
     }
 
-    fn realloc(&mut self, new_capacity: usize) {
-        unsafe {
-            let new_size = self.item_layout.size() * new_capacity;
-            let new_block = alloc::realloc(self.block.as_ptr(), self.item_layout, new_size);
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).unwrap_or_else(|err| err.handle())
+    }
+
+    #[track_caller]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.try_reserve_exact(additional).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Grows the capacity geometrically like `Vec` does, so repeated pushes are amortized O(1).
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), BlobArrayError> {
+        let required = self.len
+            .checked_add(additional)
+            .ok_or(BlobArrayError::CapacityOverflow)?;
+
+        if required > self.capacity {
+            let min_capacity = match self.item_layout.size() {
+                1 => 8,
+                size if size <= 1024 => 4,
+                _ => 1,
+            };
+            let new_capacity = self.capacity
+                .saturating_mul(2)
+                .max(required)
+                .max(min_capacity);
+            self.try_realloc(new_capacity)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), BlobArrayError> {
+        let required = self.len
+            .checked_add(additional)
+            .ok_or(BlobArrayError::CapacityOverflow)?;
 
-            self.block = NonNull::new_unchecked(new_block);
-            self.capacity = NonZeroUsize::try_from(new_capacity).unwrap();
+        if required > self.capacity {
+            self.try_realloc(required)?;
         }
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0)
+    }
+
+    #[track_caller]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let new_capacity = self.len.max(min_capacity);
+
+        if new_capacity < self.capacity {
+            self.try_realloc(new_capacity).unwrap_or_else(|err| err.handle())
+        }
+    }
+
+    fn array_layout(&self, capacity: usize) -> Result<alloc::Layout, BlobArrayError> {
+        let size = self.item_layout
+            .size()
+            .checked_mul(capacity)
+            .ok_or(BlobArrayError::CapacityOverflow)?;
+
+        alloc::Layout::from_size_align(size, self.item_layout.align())
+            .map_err(|_| BlobArrayError::CapacityOverflow)
+    }
+
+    fn try_realloc(&mut self, new_capacity: usize) -> Result<(), BlobArrayError> {
+        debug_assert!(new_capacity >= self.len);
+
+        if self.item_layout.size() == 0 { return Ok(()) }
+
+        let old_layout = self.array_layout(self.capacity)?;
+        let new_layout = self.array_layout(new_capacity)?;
+
+        let new_block = if new_layout.size() == 0 {
+            if old_layout.size() > 0 {
+                unsafe { self.alloc.deallocate(self.block, old_layout) }
+            }
+            dangling(new_layout.align())
+        } else {
+            let result = unsafe {
+                if old_layout.size() == 0 {
+                    self.alloc.allocate(new_layout)
+                } else if new_layout.size() > old_layout.size() {
+                    self.alloc.grow(self.block, old_layout, new_layout)
+                } else {
+                    self.alloc.shrink(self.block, old_layout, new_layout)
+                }
+            };
+            result
+                .map_err(|_| BlobArrayError::AllocFailed { layout: new_layout })?
+                .cast::<u8>()
+        };
+
+        self.block = new_block;
+        self.capacity = new_capacity;
+        Ok(())
     }
 
     #[inline(always)]
@@ -100,7 +449,10 @@ impl BlobArray {
         }
     }
 
-    pub fn get<T>(&self, index: usize) -> Option<&T> {
+    #[track_caller]
+    pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.assert_type::<T>();
+
         if index >= self.len { return None }
 
         unsafe {
@@ -109,16 +461,35 @@ impl BlobArray {
         }
     }
 
-    pub fn get_mut<T>(&mut self, index: usize) -> Option<&mut T> {
+    pub fn try_get<T: 'static>(&self, index: usize) -> Result<&T, BlobArrayError> {
+        self.check_type::<T>()?;
+        self.check_index(index)?;
+
+        unsafe {
+            let raw = self.get_raw::<T>(index);
+            Ok(&*raw.cast::<T>())
+        }
+    }
+
+    #[track_caller]
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        self.assert_type::<T>();
+
         if index >= self.len { return None }
 
+        self.set_changed(index);
         unsafe {
             let raw = self.get_raw::<T>(index);
             Some(&mut *raw.cast::<T>())
         }
     }
     
-    pub fn get_cell<T>(&self, index: usize) -> Option<&UnsafeCell<T>> {
+    /// Writes through the returned cell aren't seen by change detection,
+    /// use [`BlobArray::get_cell_mut`] for a write guard that stamps the changed tick.
+    #[track_caller]
+    pub fn get_cell<T: 'static>(&self, index: usize) -> Option<&UnsafeCell<T>> {
+        self.assert_type::<T>();
+
         if index >= self.len { return None }
        
         unsafe {
@@ -128,63 +499,547 @@ impl BlobArray {
         }
     }
 
-    pub fn swap_remove<T>(&mut self, index: usize) -> Option<T> {
+    #[track_caller]
+    pub fn swap_remove<T: 'static>(&mut self, index: usize) -> Option<T> {
+        self.assert_type::<T>();
+
         if index >= self.len { return None }
 
-        let last_index = self.len - 1;
+        unsafe { Some(self.swap_remove_unchecked(index)) }
+    }
+
+    pub fn try_swap_remove<T: 'static>(&mut self, index: usize) -> Result<T, BlobArrayError> {
+        self.check_type::<T>()?;
+        self.check_index(index)?;
+
+        unsafe { Ok(self.swap_remove_unchecked(index)) }
+    }
+
+    unsafe fn swap_remove_unchecked<T>(&mut self, index: usize) -> T {
+        unsafe { self.swap_remove_forget(index).cast::<T>().read() }
+    }
+
+    #[track_caller]
+    pub fn iter<T: 'static>(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slice())
+    }
+
+    /// Every element the iterator yields is marked as changed.
+    #[track_caller]
+    pub fn iter_mut<T: 'static>(&mut self) -> IterMut<'_, T> {
+        self.assert_type::<T>();
+
+        let slice = unsafe { std::slice::from_raw_parts_mut(self.block.as_ptr().cast::<T>(), self.len) };
+        let ticks = self.ticks.as_deref_mut().map(|ticks| {
+            let current = ticks.current();
+            (ticks.changed_mut().iter_mut(), current)
+        });
+
+        IterMut::new(slice, ticks)
+    }
+
+    // `IntoIterator` can't be implemented without knowing the element type
+    #[allow(clippy::should_implement_trait)]
+    #[track_caller]
+    pub fn into_iter<T: 'static>(self) -> IntoIter<T, A> {
+        self.assert_type::<T>();
+        IntoIter::new(self)
+    }
+
+    /// Removes the elements in `range`, yielding them by value. Whatever the [`Drain`] doesn't yield
+    /// is dropped with it, and the tail is moved back into place even if one of those drops panics.
+    #[track_caller]
+    pub fn drain<T: 'static>(&mut self, range: impl RangeBounds<usize>) -> Drain<'_, T, A> {
+        self.assert_type::<T>();
+        let range = resolve_range(range, self.len);
+        Drain::new(self, range)
+    }
+
+    #[track_caller]
+    pub fn as_slice<T: 'static>(&self) -> &[T] {
+        self.assert_type::<T>();
+        unsafe { std::slice::from_raw_parts(self.block.as_ptr().cast::<T>(), self.len) }
+    }
+
+    /// Marks every element as changed.
+    #[track_caller]
+    pub fn as_mut_slice<T: 'static>(&mut self) -> &mut [T] {
+        self.assert_type::<T>();
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.mark_all_changed();
+        }
+        unsafe { std::slice::from_raw_parts_mut(self.block.as_ptr().cast::<T>(), self.len) }
+    }
+
+    /// Checks the element type once and returns a safe, `Vec`-like view over the array.
+    #[track_caller]
+    pub fn typed<T: 'static>(&self) -> TypedBlobArray<'_, T, A> {
+        self.assert_type::<T>();
+        TypedBlobArray::new(self)
+    }
+
+    #[track_caller]
+    pub fn typed_mut<T: 'static>(&mut self) -> TypedBlobArrayMut<'_, T, A> {
+        self.assert_type::<T>();
+        TypedBlobArrayMut::new(self)
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Drops `len` elements starting at `start`. The caller must have already removed them from `self.len`,
+    /// so a panicking destructor leaks the rest instead of dropping anything twice.
+    pub(crate) unsafe fn drop_range(&mut self, start: usize, len: usize) {
+        if let Some(drop) = self.drop {
+            let size = self.item_layout.size();
+            unsafe {
+                let raw = self.block.as_ptr().add(start * size);
+                for i in 0..len {
+                    drop(raw.add(i * size));
+                }
+            }
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.block.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.block.as_ptr()
+    }
+
+    #[inline(always)]
+    unsafe fn get_ptr_unchecked(&self, index: usize) -> NonNull<u8> {
+        debug_assert!(index < self.len);
+        unsafe { self.block.add(index * self.item_layout.size()) }
+    }
+
+    #[track_caller]
+    pub fn get_ptr(&self, index: usize) -> NonNull<u8> {
+        self.check_index(index).unwrap_or_else(|err| err.handle());
+        unsafe { self.get_ptr_unchecked(index) }
+    }
+
+    /// Moves the value at `value` into the array.
+    ///
+    /// # Safety
+    /// `value` must point to a valid, initialized value described by the array's layout,
+    /// which is then owned by the array and must not be used or dropped by the caller.
+    #[track_caller]
+    pub unsafe fn push_raw(&mut self, value: *const u8) {
+        if self.len == self.capacity {
+            self.reserve(1);
+        }
+
+        unsafe {
+            let dst = self.block.add(self.len * self.item_layout.size());
+            std::ptr::copy_nonoverlapping(value, dst.as_ptr(), self.item_layout.size());
+        }
+
+        self.len += 1;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.push();
+        }
+    }
+
+    /// Moves the value at `index` into `out`, replacing it with the last element.
+    ///
+    /// # Safety
+    /// `out` must be valid for writes of the array's layout and must not overlap the array.
+    /// The caller takes ownership of the written value.
+    #[track_caller]
+    pub unsafe fn swap_remove_raw(&mut self, index: usize, out: *mut u8) {
+        self.check_index(index).unwrap_or_else(|err| err.handle());
 
         unsafe {
-            let last = self.get_raw::<T>(last_index).cast::<T>();
+            let removed = self.swap_remove_forget(index);
+            std::ptr::copy_nonoverlapping(removed.as_ptr(), out, self.item_layout.size());
+        }
+    }
+
+    #[track_caller]
+    pub fn swap_remove_and_drop(&mut self, index: usize) {
+        self.check_index(index).unwrap_or_else(|err| err.handle());
+
+        unsafe {
+            let removed = self.swap_remove_forget(index);
+            if let Some(drop) = self.drop {
+                drop(removed.as_ptr());
+            }
+        }
+    }
+
+    /// Swaps the element at `index` with the last one and shrinks `len` past it without dropping.
+    /// The returned pointer stays valid until the array is pushed to or reallocated.
+    pub(crate) unsafe fn swap_remove_forget(&mut self, index: usize) -> NonNull<u8> {
+        unsafe {
+            let to_remove = self.get_ptr_unchecked(index);
+            let last = self.get_ptr_unchecked(self.len - 1);
+
             self.len -= 1;
+            if index < self.len {
+                std::ptr::swap_nonoverlapping(to_remove.as_ptr(), last.as_ptr(), self.item_layout.size());
+            }
 
-            if index < last_index {
-                let to_remove = self.get_raw::<T>(index).cast::<T>();
-                std::ptr::swap_nonoverlapping(to_remove, last, 1);
-                Some(last.read())
-            } else {
-                Some(last.read())
+            if let Some(ticks) = self.ticks.as_deref_mut() {
+                ticks.swap_remove(index);
             }
+
+            last
         }
     }
 
-    pub fn iter<'a, T>(&'a self) -> Iter<'a, T> {
-        Iter::new(self)
+    #[track_caller]
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        self.assert_type::<T>();
+        unsafe { self.pop_unchecked() }
     }
 
-    pub fn clear(&mut self) {
+    pub(crate) unsafe fn pop_unchecked<T>(&mut self) -> Option<T> {
+        self.pop_forget().map(|last| unsafe { last.cast::<T>().read() })
+    }
+
+    /// Shrinks `len` past the last element without dropping it, see [`BlobArray::swap_remove_forget`].
+    fn pop_forget(&mut self) -> Option<NonNull<u8>> {
+        if self.len == 0 { return None }
+
+        self.len -= 1;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.truncate(self.len);
+        }
+        unsafe { Some(self.block.add(self.len * self.item_layout.size())) }
+    }
+
+    /// Moves the last element into `out`, returning `false` if the array is empty.
+    ///
+    /// # Safety
+    /// `out` must be valid for writes of the array's layout and must not overlap the array.
+    /// The caller takes ownership of the written value.
+    pub unsafe fn pop_raw(&mut self, out: *mut u8) -> bool {
+        let Some(last) = self.pop_forget() else { return false };
+
+        unsafe { std::ptr::copy_nonoverlapping(last.as_ptr(), out, self.item_layout.size()) }
+        true
+    }
+
+    pub fn pop_and_drop(&mut self) -> bool {
+        let Some(last) = self.pop_forget() else { return false };
+
         if let Some(drop) = self.drop {
-            self.drop = None;
-            unsafe { drop(self.block.as_ptr(), self.len) }
-            self.drop = Some(drop);
-            self.len = 0;
+            unsafe { drop(last.as_ptr()) }
         }
+        true
     }
-}
 
-pub struct Iter<'a, T> {
-    source: &'a BlobArray,
-    next: usize,
-    marker: PhantomData<UnsafeCell<T>>,
-}
+    /// Removes the element at `index`, shifting everything after it to the left.
+    #[track_caller]
+    pub fn remove<T: 'static>(&mut self, index: usize) -> Option<T> {
+        self.assert_type::<T>();
 
-impl<'a, T> Iter<'a, T> {
-    fn new(source: &'a BlobArray) -> Self {
-        Self {
-            source,
-            next: 0,
-            marker: PhantomData,
+        if index >= self.len { return None }
+
+        let mut out = mem::MaybeUninit::<T>::uninit();
+        unsafe {
+            self.remove_raw(index, out.as_mut_ptr().cast());
+            Some(out.assume_init())
+        }
+    }
+
+    /// # Safety
+    /// `out` must be valid for writes of the array's layout and must not overlap the array.
+    /// The caller takes ownership of the written value.
+    #[track_caller]
+    pub unsafe fn remove_raw(&mut self, index: usize, out: *mut u8) {
+        self.check_index(index).unwrap_or_else(|err| err.handle());
+
+        let size = self.item_layout.size();
+        unsafe {
+            let to_remove = self.get_ptr_unchecked(index).as_ptr();
+            std::ptr::copy_nonoverlapping(to_remove, out, size);
+            std::ptr::copy(to_remove.add(size), to_remove, (self.len - index - 1) * size);
+        }
+        self.len -= 1;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.remove(index);
+        }
+    }
+
+    #[track_caller]
+    pub fn remove_and_drop(&mut self, index: usize) {
+        self.check_index(index).unwrap_or_else(|err| err.handle());
+
+        let mut current = 0;
+        self.compact(|_, _| {
+            current += 1;
+            current - 1 != index
+        })
+    }
+
+    /// Inserts `value` at `index`, shifting everything after it to the right.
+    #[track_caller]
+    pub fn insert<T: 'static>(&mut self, index: usize, value: T) {
+        self.assert_type::<T>();
+
+        let value = mem::ManuallyDrop::new(value);
+        unsafe { self.insert_raw(index, (&raw const value).cast()) }
+    }
+
+    /// # Safety
+    /// Same as [`BlobArray::push_raw`].
+    #[track_caller]
+    pub unsafe fn insert_raw(&mut self, index: usize, value: *const u8) {
+        assert!(index <= self.len, "insertion index (is {index}) should be <= len (is {})", self.len);
+
+        if self.len == self.capacity {
+            self.reserve(1);
+        }
+
+        let size = self.item_layout.size();
+        unsafe {
+            let dst = self.block.as_ptr().add(index * size);
+            std::ptr::copy(dst, dst.add(size), (self.len - index) * size);
+            std::ptr::copy_nonoverlapping(value, dst, size);
+        }
+        self.len += 1;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.insert(index);
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len { return }
+
+        let old_len = self.forget_from(len);
+        unsafe { self.drop_range(len, old_len - len) }
+    }
+
+    /// Shrinks `len` to `len` without dropping anything and returns the old length,
+    /// the forgotten elements can then be dropped with `drop_range`.
+    pub(crate) fn forget_from(&mut self, len: usize) -> usize {
+        if len >= self.len { return self.len }
+
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.truncate(len);
+        }
+        mem::replace(&mut self.len, len)
+    }
+
+    #[track_caller]
+    pub fn retain<T: 'static>(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.retain_mut::<T>(|value| f(value))
+    }
+
+    #[track_caller]
+    pub fn retain_mut<T: 'static>(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        self.assert_type::<T>();
+        self.retain_raw(|ptr| f(unsafe { ptr.cast::<T>().as_mut() }))
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, dropping the rest with the stored drop fn.
+    pub fn retain_raw(&mut self, mut f: impl FnMut(NonNull<u8>) -> bool) {
+        self.compact(|current, _| f(current))
+    }
+
+    #[track_caller]
+    pub fn dedup_by<T: 'static>(&mut self, mut same_bucket: impl FnMut(&mut T, &mut T) -> bool) {
+        self.assert_type::<T>();
+        self.dedup_by_raw(|a, b| unsafe { same_bucket(a.cast::<T>().as_mut(), b.cast::<T>().as_mut()) })
+    }
+
+    /// Removes consecutive elements for which `same_bucket(current, previous)` returns `true`.
+    pub fn dedup_by_raw(&mut self, mut same_bucket: impl FnMut(NonNull<u8>, NonNull<u8>) -> bool) {
+        self.compact(|current, previous| previous.is_none_or(|previous| !same_bucket(current, previous)))
+    }
+
+    /// Walks the array front to back, packing the elements `keep(current, last_kept)` accepts at the front and
+    /// dropping the others. If `keep` or a destructor panics, the unvisited tail is moved down over the holes.
+    fn compact(&mut self, mut keep: impl FnMut(NonNull<u8>, Option<NonNull<u8>>) -> bool) {
+        struct Guard<'a, A: Allocator> {
+            source: &'a mut BlobArray<A>,
+            read: usize,
+            write: usize,
+            len: usize,
+        }
+
+        impl<A: Allocator> Drop for Guard<'_, A> {
+            fn drop(&mut self) {
+                let size = self.source.item_layout.size();
+                unsafe {
+                    let base = self.source.block.as_ptr();
+                    let tail = self.len - self.read;
+                    std::ptr::copy(base.add(self.read * size), base.add(self.write * size), tail * size);
+                    self.source.len = self.write + tail;
+                }
+
+                if let Some(ticks) = self.source.ticks.as_deref_mut() {
+                    ticks.copy_within(self.read..self.len, self.write);
+                    ticks.truncate(self.source.len);
+                }
+            }
+        }
+
+        let len = mem::replace(&mut self.len, 0);
+        let size = self.item_layout.size();
+        let drop = self.drop;
+        let base = self.block;
+
+        let mut guard = Guard {
+            source: self,
+            read: 0,
+            write: 0,
+            len,
+        };
+
+        while guard.read < guard.len {
+            unsafe {
+                let current = base.add(guard.read * size);
+                let last_kept = (guard.write > 0).then(|| base.add((guard.write - 1) * size));
+
+                if keep(current, last_kept) {
+                    if guard.read != guard.write {
+                        std::ptr::copy_nonoverlapping(current.as_ptr(), base.add(guard.write * size).as_ptr(), size);
+                        if let Some(ticks) = guard.source.ticks.as_deref_mut() {
+                            ticks.copy(guard.read, guard.write);
+                        }
+                    }
+                    guard.read += 1;
+                    guard.write += 1;
+                } else {
+                    guard.read += 1;
+                    if let Some(drop) = drop {
+                        drop(current.as_ptr());
+                    }
+                }
+            }
+        }
+    }
+
+    #[track_caller]
+    pub fn append(&mut self, other: &mut Self) {
+        self.try_append(other).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Moves every element of `other` into `self`, leaving `other` empty. Both arrays must store the same type,
+    /// or have the same layout if either of them is type erased.
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), BlobArrayError> {
+        match (self.type_id, other.type_id) {
+            // appending must not hand erased elements to a typed array, or the other way around
+            (expected, found) if expected != found => {
+                return Err(BlobArrayError::TypeMismatch {
+                    expected: self.type_name,
+                    found: other.type_name,
+                })
+            }
+            _ if self.item_layout != other.item_layout => {
+                return Err(BlobArrayError::LayoutMismatch {
+                    expected: self.item_layout,
+                    found: other.item_layout,
+                })
+            }
+            _ => {}
+        }
+
+        self.try_reserve(other.len)?;
+
+        let size = self.item_layout.size();
+        unsafe {
+            let dst = self.block.as_ptr().add(self.len * size);
+            std::ptr::copy_nonoverlapping(other.block.as_ptr(), dst, other.len * size);
+        }
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.append(other.ticks.as_deref_mut(), other.len);
+        } else if let Some(ticks) = other.ticks.as_deref_mut() {
+            ticks.truncate(0);
+        }
+        self.len += mem::replace(&mut other.len, 0);
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn extend_from_iter<T: 'static, I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.assert_type::<T>();
+        unsafe { self.extend_unchecked(iter) }
+    }
+
+    pub(crate) unsafe fn extend_unchecked<T, I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            unsafe { self.try_push_unchecked(value).unwrap_or_else(|err| err.handle()) }
         }
     }
 }
 
-impl<'a, T: 'a> Iterator for Iter<'a, T> {
-    type Item = &'a UnsafeCell<T>;
+impl<A: Allocator + Clone> BlobArray<A> {
+    fn empty_like(&self, capacity: usize) -> Result<Self, BlobArrayError> {
+        let mut this = Self::erased(self.item_layout, self.drop, self.alloc.clone());
+        this.type_id = self.type_id;
+        this.type_name = self.type_name;
+        this.clone = self.clone;
+        this.send = self.send;
+        this.sync = self.sync;
+        this.ticks = self.ticks.as_ref().map(|ticks| Box::new(ChangeTicks::new(0, ticks.current())));
+        this.try_realloc(capacity)?;
+        Ok(this)
+    }
+
+    /// Splits the array in two at `at`, returning the elements `[at, len)` in a new array of the same type.
+    #[track_caller]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "`at` split index (is {at}) should be <= len (is {})", self.len);
+
+        let tail = self.len - at;
+        let mut other = self.empty_like(tail).unwrap_or_else(|err| err.handle());
+
+        unsafe {
+            let src = self.block.as_ptr().add(at * self.item_layout.size());
+            std::ptr::copy_nonoverlapping(src, other.block.as_ptr(), tail * self.item_layout.size());
+        }
+        self.len = at;
+        other.len = tail;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            other.ticks = Some(Box::new(ticks.split_off(at)));
+        }
+
+        other
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.source
-            .get_cell::<T>(self.next)
-            .inspect(|_| self.next += 1)
+/// The type erased destructor stored next to values of `T`, if `T` needs one.
+fn drop_fn<T>() -> Option<unsafe fn(*mut u8)> {
+    #[inline]
+    unsafe fn drop<T>(raw: *mut u8) {
+        unsafe { std::ptr::drop_in_place(raw.cast::<T>()) }
     }
+
+    mem::needs_drop::<T>().then_some(drop::<T>)
+}
+
+#[inline(always)]
+fn dangling(align: usize) -> NonNull<u8> {
+    unsafe { NonNull::new_unchecked(std::ptr::without_provenance_mut(align)) }
+}
+
+#[track_caller]
+fn resolve_range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflowed"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflowed"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    assert!(start <= end, "range start {start} is greater than range end {end}");
+    assert!(end <= len, "range end {end} is out of bounds for length {len}");
+
+    start..end
 }
 
 #[cfg(test)]
@@ -251,17 +1106,260 @@ mod test {
             ba.push(Obj { name: i.to_string(), age: i as _ });
         }
 
-        let iter = ba.iter::<Obj>();
-        iter.for_each(|cell| unsafe {
-            let obj = &mut *cell.get();
-            obj.age = 0;
-        });
+        let iter = ba.iter_mut::<Obj>();
+        iter.for_each(|obj| obj.age = 0);
 
         let mut iter2 = ba.iter::<Obj>();
-        assert!(iter2.all(|cell| unsafe {
-            let obj = &*cell.get();
-            obj.age == 0
-        }))
+        assert!(iter2.all(|obj| obj.age == 0))
+    }
+
+    #[test]
+    #[should_panic(expected = "type mismatch")]
+    fn wrong_type_push() {
+        let mut ba = BlobArray::new::<u32>(1);
+        ba.push(1u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "type mismatch")]
+    fn wrong_type_get() {
+        let mut ba = BlobArray::new::<Obj>(1);
+        ba.push(Obj { name: "Balo".to_string(), age: 69 });
+        ba.get::<u32>(0);
+    }
+
+    #[test]
+    fn fallible() {
+        let mut ba = BlobArray::try_new::<u32>(0).unwrap();
+        assert_eq!(ba.capacity(), 0);
+
+        ba.try_push(7u32).unwrap();
+        assert_eq!(ba.try_get::<u32>(0), Ok(&7));
+        assert!(matches!(ba.try_push(7u64), Err(BlobArrayError::TypeMismatch { .. })));
+        assert_eq!(ba.try_get::<u32>(1), Err(BlobArrayError::IndexOutOfBounds { index: 1, len: 1 }));
+        assert_eq!(ba.try_reserve(usize::MAX), Err(BlobArrayError::CapacityOverflow));
+        assert_eq!(ba.try_swap_remove::<u32>(0), Ok(7));
+        assert!(ba.try_swap_remove::<u32>(0).is_err());
+
+        assert_eq!(BlobArray::try_new::<u64>(usize::MAX).err(), Some(BlobArrayError::CapacityOverflow));
+    }
+
+    #[test]
+    fn growth() {
+        let mut ba = BlobArray::new::<usize>(0);
+
+        let mut reallocs = 0;
+        let mut capacity = ba.capacity();
+        for i in 0..1000usize {
+            ba.push(i);
+            if ba.capacity() != capacity {
+                capacity = ba.capacity();
+                reallocs += 1;
+            }
+        }
+        assert!(reallocs <= 10);
+        assert!((0..1000).all(|i| ba.get::<usize>(i) == Some(&i)));
+
+        ba.shrink_to(2000);
+        assert!(ba.capacity() >= 1000);
+        ba.shrink_to_fit();
+        assert_eq!(ba.capacity(), 1000);
+
+        ba.reserve_exact(1);
+        assert_eq!(ba.capacity(), 1001);
+        ba.reserve(2);
+        assert_eq!(ba.capacity(), 2002);
+    }
+
+    #[test]
+    fn type_erased() {
+        unsafe fn drop_obj(raw: *mut u8) {
+            unsafe { std::ptr::drop_in_place(raw.cast::<Obj>()) }
+        }
+
+        let mut ba = unsafe { BlobArray::from_layout(alloc::Layout::new::<Obj>(), Some(drop_obj), None, 0) };
+        assert_eq!(ba.type_id(), None);
+
+        for i in 0..4 {
+            let obj = mem::ManuallyDrop::new(Obj { name: i.to_string(), age: i });
+            unsafe { ba.push_raw((&raw const obj).cast()) }
+        }
+
+        let mut out = mem::MaybeUninit::<Obj>::uninit();
+        unsafe { ba.swap_remove_raw(0, out.as_mut_ptr().cast()) }
+        let removed = unsafe { out.assume_init() };
+        assert_eq!(removed.age, 0);
+
+        ba.swap_remove_and_drop(0);
+        assert_eq!(ba.len(), 2);
+
+        let first = unsafe { ba.get_ptr(0).cast::<Obj>().as_ref() };
+        assert_eq!(first.age, 2);
+        assert!(matches!(ba.try_get::<Obj>(1), Err(BlobArrayError::TypeMismatch { .. })));
+
+        let id = Some(TypeId::of::<Obj>());
+        let mut ba = unsafe { BlobArray::from_layout(alloc::Layout::new::<Obj>(), Some(drop_obj), id, 0) };
+        ba.push(Obj { name: "Ochi".to_string(), age: 1 });
+        assert_eq!(ba.get::<Obj>(0).map(|obj| obj.age), Some(1));
+        assert!(matches!(ba.try_get::<u8>(0), Err(BlobArrayError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn vec_parity() {
+        let mut ba = BlobArray::new::<u32>(0);
+        ba.extend_from_iter(0..10u32);
+
+        ba.insert(0, 100u32);
+        assert_eq!(ba.remove::<u32>(1), Some(0));
+        assert_eq!(ba.pop::<u32>(), Some(9));
+        ba.retain::<u32>(|value| value % 2 == 0);
+        assert_eq!(ba.as_slice::<u32>(), &[100, 2, 4, 6, 8]);
+
+        let mut tail = ba.split_off(3);
+        assert_eq!(tail.as_slice::<u32>(), &[6, 8]);
+        ba.truncate(1);
+        ba.append(&mut tail);
+        assert!(tail.is_empty());
+        assert_eq!(ba.as_slice::<u32>(), &[100, 6, 8]);
+
+        ba.extend_from_iter([8u32, 8, 6, 6]);
+        ba.dedup_by::<u32>(|a, b| a == b);
+        assert_eq!(ba.as_slice::<u32>(), &[100, 6, 8, 6]);
+
+        let mut other = BlobArray::new::<i32>(0);
+        assert!(matches!(ba.try_append(&mut other), Err(BlobArrayError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn untyped_mutation() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ba = BlobArray::new::<Rc<()>>(0);
+        for _ in 0..8 {
+            ba.push(counter.clone());
+        }
+
+        ba.remove_and_drop(2);
+        assert!(ba.pop_and_drop());
+        ba.truncate(5);
+        assert_eq!(Rc::strong_count(&counter), 6);
+
+        let mut index = 0;
+        ba.retain_raw(|_| {
+            index += 1;
+            index % 2 == 0
+        });
+        assert_eq!(ba.len(), 2);
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        let mut out = mem::MaybeUninit::<Rc<()>>::uninit();
+        unsafe {
+            ba.remove_raw(0, out.as_mut_ptr().cast());
+            ba.insert_raw(1, out.as_ptr().cast());
+        }
+        assert_eq!(ba.len(), 2);
+
+        ba.dedup_by_raw(|_, _| true);
+        assert_eq!(ba.len(), 1);
+        assert_eq!(Rc::strong_count(&counter), 2);
+    }
+
+    #[test]
+    fn retain_panic() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ba = BlobArray::new::<Rc<()>>(0);
+        for _ in 0..6 {
+            ba.push(counter.clone());
+        }
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut index = 0;
+            ba.retain::<Rc<()>>(|_| {
+                index += 1;
+                if index == 4 { panic!() }
+                index != 2
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(ba.len(), 5);
+        assert_eq!(Rc::strong_count(&counter), 6);
+    }
+
+    #[test]
+    fn custom_allocator() {
+        use std::cell::Cell;
+
+        #[repr(align(64))]
+        struct Bump {
+            buf: UnsafeCell<[u8; 1024]>,
+            offset: Cell<usize>,
+            grown_in_place: Cell<usize>,
+        }
+
+        impl Bump {
+            fn base(&self) -> *mut u8 {
+                self.buf.get().cast()
+            }
+
+            fn contains(&self, ptr: *const u8) -> bool {
+                (self.base() as *const u8..self.base().wrapping_add(1024)).contains(&ptr)
+            }
+        }
+
+        unsafe impl Allocator for Bump {
+            fn allocate(&self, layout: alloc::Layout) -> Result<NonNull<[u8]>, AllocError> {
+                let start = self.offset.get().next_multiple_of(layout.align());
+                if start + layout.size() > 1024 { return Err(AllocError) }
+
+                self.offset.set(start + layout.size());
+                let ptr = unsafe { NonNull::new_unchecked(self.base().add(start)) };
+                Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+            }
+
+            unsafe fn deallocate(&self, _: NonNull<u8>, _: alloc::Layout) {}
+
+            unsafe fn grow(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: alloc::Layout,
+                new_layout: alloc::Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                let start = ptr.as_ptr() as usize - self.base() as usize;
+                if start + old_layout.size() == self.offset.get() && start + new_layout.size() <= 1024 {
+                    self.offset.set(start + new_layout.size());
+                    self.grown_in_place.set(self.grown_in_place.get() + 1);
+                    return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+                }
+
+                let new_block = self.allocate(new_layout)?;
+                unsafe { std::ptr::copy_nonoverlapping(ptr.as_ptr(), new_block.cast().as_ptr(), old_layout.size()) }
+                Ok(new_block)
+            }
+        }
+
+        let bump = Bump {
+            buf: UnsafeCell::new([0; 1024]),
+            offset: Cell::new(0),
+            grown_in_place: Cell::new(0),
+        };
+
+        let mut ba = BlobArray::new_in::<u64>(0, &bump);
+        for i in 0..64u64 {
+            ba.push(i);
+        }
+        assert!(bump.contains(ba.as_ptr()));
+        assert!(bump.grown_in_place.get() > 0);
+        assert!((0..64).all(|i| ba.get::<u64>(i as usize) == Some(&i)));
+
+        ba.shrink_to(32);
+        assert!(bump.contains(ba.as_ptr()));
+        assert!(ba.try_reserve(1024).is_err());
+
+        let into_iter = ba.into_iter::<u64>();
+        assert_eq!(into_iter.sum::<u64>(), (0..64).sum());
     }
 
     #[test]
@@ -269,9 +1367,12 @@ mod test {
         const CAP: usize = 2;
         struct Zst;
         let mut ba = BlobArray::new::<Zst>(CAP);
-        for _ in 0..CAP {
+        assert_eq!(ba.capacity(), usize::MAX);
+
+        for _ in 0..CAP * 4 {
             ba.push(Zst);
         }
+        assert_eq!(ba.capacity(), usize::MAX);
 
         unsafe {
             let first = ba.get_raw::<Zst>(0) as usize;
@@ -279,6 +1380,40 @@ mod test {
 
             assert_eq!(first, second);
         }
+
+        ba.shrink_to_fit();
+        assert_eq!(ba.capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn zst_drop() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        #[repr(align(64))]
+        struct Zst;
+
+        impl Drop for Zst {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut ba = BlobArray::new::<Zst>(0);
+        for _ in 0..10 {
+            ba.push(Zst);
+        }
+        assert_eq!(ba.get_ptr(3).as_ptr() as usize % 64, 0);
+
+        drop(ba.swap_remove::<Zst>(3));
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+
+        ba.swap_remove_and_drop(0);
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 2);
+
+        drop(ba);
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 10);
     }
 
     #[test]
@@ -305,8 +1440,3 @@ mod test {
         println!("vec push time for {NUM} objects: {:?}", now.elapsed());
     }
 }
-
-// struct ElementInfo {
-//     layout: alloc::Layout,
-//     type_id: std::any::TypeId,
-// }
@@ -0,0 +1,414 @@
+use std::any::TypeId;
+use std::ffi::{c_int, c_void};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::ptr::NonNull;
+
+use crate::format::{HEADER_LEN, Header, LEN_OFFSET};
+use crate::{BlobArrayError, FormatError, Iter, Pod};
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+#[cfg(target_os = "linux")]
+const MS_SYNC: c_int = 4;
+#[cfg(target_os = "macos")]
+const MS_SYNC: c_int = 0x10;
+
+// only declared for 64-bit Linux and macOS, where `off_t` is an `i64` and the constants above are correct
+unsafe extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: i64) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int;
+}
+
+/// A [`BlobArray`](crate::BlobArray) of [`Pod`] elements living in a memory-mapped file,
+/// using the same header and element layout as [`BlobArray::write_to`](crate::BlobArray::write_to).
+///
+/// The file is kept larger than needed while the array is open, and is trimmed back to its `len` on drop.
+/// Elements are aligned to at most 64 bytes, which is where the data starts within the page aligned mapping.
+pub struct MmapBlobArray {
+    file: File,
+    map: NonNull<u8>,
+    map_len: usize,
+    len: usize,
+    capacity: usize,
+    item_size: usize,
+    type_id: TypeId,
+    type_name: &'static str,
+    writable: bool,
+}
+
+// SAFETY: the mapping only ever holds `Pod` values, which are `Send` and `Sync`, and the unsafe constructors
+// rule out any other handle to the same file
+unsafe impl Send for MmapBlobArray {}
+unsafe impl Sync for MmapBlobArray {}
+
+impl MmapBlobArray {
+    /// Creates or truncates the file at `path`, with room for `capacity` elements.
+    ///
+    /// # Safety
+    /// The mapping is shared with the file, so while the array is alive the file must not be mapped again,
+    /// written to, or truncated, by this process or any other. Otherwise the slices handed out by the array
+    /// alias other writes, or fault once the pages behind them are gone.
+    pub unsafe fn create<T: Pod>(path: impl AsRef<Path>, capacity: usize, type_hash: Option<u64>) -> Result<Self, FormatError> {
+        check_align::<T>()?;
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.write_all(&Header::new::<T>(0, type_hash).encode())?;
+
+        let mut this = Self::map::<T>(file, 0, true)?;
+        this.try_reserve(capacity)?;
+        Ok(this)
+    }
+
+    /// Maps an existing file for reading and writing.
+    ///
+    /// # Safety
+    /// See [`MmapBlobArray::create`].
+    pub unsafe fn open<T: Pod>(path: impl AsRef<Path>, type_hash: Option<u64>) -> Result<Self, FormatError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Self::open_file::<T>(file, type_hash, true)
+    }
+
+    /// Maps an existing file without write access, every mutating method panics or fails.
+    ///
+    /// # Safety
+    /// The file must not be written to or truncated while the array is alive,
+    /// see [`MmapBlobArray::create`].
+    pub unsafe fn open_read_only<T: Pod>(path: impl AsRef<Path>, type_hash: Option<u64>) -> Result<Self, FormatError> {
+        let file = File::open(path)?;
+        Self::open_file::<T>(file, type_hash, false)
+    }
+
+    fn open_file<T: Pod>(file: File, type_hash: Option<u64>, writable: bool) -> Result<Self, FormatError> {
+        check_align::<T>()?;
+        let mut header = [0; HEADER_LEN];
+        std::io::Read::read_exact(&mut &file, &mut header)?;
+        let header = Header::decode(&header)?;
+        header.validate::<T>(type_hash)?;
+
+        let data_len = header.data_len()?;
+        let file_len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| FormatError::Array(BlobArrayError::CapacityOverflow))?;
+        if file_len - HEADER_LEN < data_len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+        }
+
+        Self::map::<T>(file, header.len, writable)
+    }
+
+    fn map<T: Pod>(file: File, len: usize, writable: bool) -> Result<Self, FormatError> {
+        let mut this = Self {
+            file,
+            map: NonNull::dangling(),
+            map_len: 0,
+            len,
+            capacity: 0,
+            item_size: size_of::<T>(),
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            writable,
+        };
+        this.remap()?;
+        Ok(this)
+    }
+
+    /// Maps the whole file, the capacity is whatever fits after the header.
+    /// The old mapping is only released once the new one succeeded, so a failure leaves the array untouched.
+    fn remap(&mut self) -> io::Result<()> {
+        let map_len = usize::try_from(self.file.metadata()?.len()).map_err(io::Error::other)?;
+        let prot = if self.writable { PROT_READ | PROT_WRITE } else { PROT_READ };
+
+        let ptr = unsafe { mmap(std::ptr::null_mut(), map_len, prot, MAP_SHARED, self.file.as_raw_fd(), 0) };
+        if ptr as isize == -1 {
+            return Err(io::Error::last_os_error())
+        }
+
+        let Some(map) = NonNull::new(ptr.cast()) else {
+            return Err(io::Error::other("mmap returned null"))
+        };
+
+        self.unmap();
+        self.map = map;
+        self.map_len = map_len;
+        self.capacity = match self.item_size {
+            0 => usize::MAX,
+            size => (map_len - HEADER_LEN) / size,
+        };
+        Ok(())
+    }
+
+    fn unmap(&mut self) {
+        if self.map_len != 0 {
+            unsafe { munmap(self.map.as_ptr().cast(), self.map_len) };
+            self.map_len = 0;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        !self.writable
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn assert_type<T: 'static>(&self) {
+        if !self.is::<T>() {
+            BlobArrayError::TypeMismatch {
+                expected: self.type_name,
+                found: std::any::type_name::<T>(),
+            }.handle()
+        }
+    }
+
+    fn check_writable(&self) -> io::Result<()> {
+        match self.writable {
+            true => Ok(()),
+            false => Err(io::Error::new(io::ErrorKind::PermissionDenied, "MmapBlobArray is read-only")),
+        }
+    }
+
+    fn data(&self) -> *mut u8 {
+        unsafe { self.map.as_ptr().add(HEADER_LEN) }
+    }
+
+    #[track_caller]
+    pub fn as_slice<T: Pod>(&self) -> &[T] {
+        self.assert_type::<T>();
+        unsafe { std::slice::from_raw_parts(self.data().cast::<T>(), self.len) }
+    }
+
+    /// Panics if the file was opened read-only.
+    #[track_caller]
+    pub fn as_mut_slice<T: Pod>(&mut self) -> &mut [T] {
+        self.assert_type::<T>();
+        self.check_writable().unwrap_or_else(|err| panic!("{err}"));
+        unsafe { std::slice::from_raw_parts_mut(self.data().cast::<T>(), self.len) }
+    }
+
+    #[track_caller]
+    pub fn get<T: Pod>(&self, index: usize) -> Option<&T> {
+        self.as_slice::<T>().get(index)
+    }
+
+    #[track_caller]
+    pub fn get_mut<T: Pod>(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice::<T>().get_mut(index)
+    }
+
+    #[track_caller]
+    pub fn iter<T: Pod>(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slice())
+    }
+
+    /// Grows the file and remaps it, the mapping may move so no references can be held across this.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), FormatError> {
+        self.check_writable()?;
+        let required = self.len.checked_add(additional).ok_or(BlobArrayError::CapacityOverflow)?;
+        if required <= self.capacity {
+            return Ok(())
+        }
+
+        let new_capacity = required.max(self.capacity.saturating_mul(2)).max(8);
+        let file_len = new_capacity
+            .checked_mul(self.item_size)
+            .and_then(|bytes| bytes.checked_add(HEADER_LEN))
+            .ok_or(BlobArrayError::CapacityOverflow)?;
+
+        // growing the file leaves the current mapping valid, so it can be kept until the new one is in place
+        self.file.set_len(file_len as u64)?;
+        self.remap()?;
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn push<T: Pod>(&mut self, value: T) {
+        self.try_push(value).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    #[track_caller]
+    pub fn try_push<T: Pod>(&mut self, value: T) -> Result<(), FormatError> {
+        self.try_extend_from_slice(&[value])
+    }
+
+    #[track_caller]
+    pub fn extend_from_slice<T: Pod>(&mut self, values: &[T]) {
+        self.try_extend_from_slice(values).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    #[track_caller]
+    pub fn try_extend_from_slice<T: Pod>(&mut self, values: &[T]) -> Result<(), FormatError> {
+        self.assert_type::<T>();
+        self.try_reserve(values.len())?;
+        unsafe {
+            let dst = self.data().cast::<T>().add(self.len);
+            std::ptr::copy_nonoverlapping(values.as_ptr(), dst, values.len());
+        }
+        self.set_len(self.len + values.len());
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn pop<T: Pod>(&mut self) -> Option<T> {
+        let value = *self.as_mut_slice::<T>().last()?;
+        self.set_len(self.len - 1);
+        Some(value)
+    }
+
+    /// Panics if the file was opened read-only.
+    #[track_caller]
+    pub fn truncate(&mut self, len: usize) {
+        self.check_writable().unwrap_or_else(|err| panic!("{err}"));
+        if len < self.len {
+            self.set_len(len);
+        }
+    }
+
+    #[track_caller]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    fn set_len(&mut self, len: usize) {
+        self.len = len;
+        unsafe {
+            let dst = self.map.as_ptr().add(LEN_OFFSET);
+            std::ptr::copy_nonoverlapping((len as u64).to_le_bytes().as_ptr(), dst, 8);
+        }
+    }
+
+    /// Writes the mapped pages back to the file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.check_writable()?;
+        match unsafe { msync(self.map.as_ptr().cast(), self.map_len, MS_SYNC) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+impl Drop for MmapBlobArray {
+    fn drop(&mut self) {
+        self.unmap();
+        if self.writable {
+            // trim the spare capacity, leaving exactly what `BlobArray::write_to` would have written
+            let _ = self.file.set_len((HEADER_LEN + self.len * self.item_size) as u64);
+        }
+    }
+}
+
+impl std::fmt::Debug for MmapBlobArray {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MmapBlobArray")
+            .field("type_name", &self.type_name)
+            .field("len", &self.len)
+            .field("capacity", &self.capacity)
+            .field("read_only", &!self.writable)
+            .finish()
+    }
+}
+
+fn check_align<T>() -> Result<(), FormatError> {
+    match align_of::<T>() <= HEADER_LEN {
+        true => Ok(()),
+        false => Err(io::Error::new(io::ErrorKind::Unsupported, "MmapBlobArray elements can be aligned to at most 64").into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::BlobArray;
+
+    struct TempFile(std::path::PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("blob_array_{}_{name}", std::process::id())))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn grow_and_reopen() {
+        let path = TempFile::new("grow");
+        {
+            let mut mapped = unsafe { MmapBlobArray::create::<u64>(&path.0, 0, None) }.unwrap();
+            for i in 0..1000u64 {
+                mapped.push(i);
+            }
+            assert!(mapped.capacity() >= 1000);
+            *mapped.get_mut::<u64>(0).unwrap() = 42;
+            assert_eq!(mapped.pop::<u64>(), Some(999));
+            mapped.flush().unwrap();
+        }
+
+        let len = std::fs::metadata(&path.0).unwrap().len();
+        assert_eq!(len as usize, HEADER_LEN + 999 * 8);
+
+        let mut mapped = unsafe { MmapBlobArray::open::<u64>(&path.0, None) }.unwrap();
+        assert_eq!(mapped.len(), 999);
+        assert_eq!(mapped.get::<u64>(0), Some(&42));
+        assert!(mapped.iter::<u64>().skip(1).copied().eq(1..999));
+        mapped.extend_from_slice(&[7u64, 8]);
+        drop(mapped);
+
+        let read = BlobArray::read_from::<u64>(std::fs::File::open(&path.0).unwrap(), None).unwrap();
+        assert_eq!(read.len(), 1001);
+        assert_eq!(read.as_slice::<u64>()[999..], [7, 8]);
+    }
+
+    #[test]
+    fn shares_write_to_format() {
+        let path = TempFile::new("format");
+        let mut ba = BlobArray::new::<[f32; 2]>(0);
+        ba.extend_from_iter([[1.0f32, 2.0], [3.0, 4.0]]);
+        ba.write_to::<[f32; 2]>(std::fs::File::create(&path.0).unwrap(), Some(7)).unwrap();
+
+        let mut mapped = unsafe { MmapBlobArray::open_read_only::<[f32; 2]>(&path.0, Some(7)) }.unwrap();
+        assert!(mapped.is_read_only());
+        assert_eq!(mapped.as_slice::<[f32; 2]>(), ba.as_slice::<[f32; 2]>());
+        assert!(matches!(mapped.try_push([0.0f32; 2]), Err(FormatError::Io(_))));
+        assert_eq!(mapped.len(), 2);
+
+        assert!(matches!(
+            unsafe { MmapBlobArray::open::<u64>(&path.0, None) },
+            Err(FormatError::LayoutMismatch { .. }),
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_only_mut_panics() {
+        let path = TempFile::new("read_only");
+        drop(unsafe { MmapBlobArray::create::<u32>(&path.0, 4, None) }.unwrap());
+        let mut mapped = unsafe { MmapBlobArray::open_read_only::<u32>(&path.0, None) }.unwrap();
+        mapped.as_mut_slice::<u32>();
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::VecDeque;
+use std::iter::Sum;
+use std::mem;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::thread;
+
+use crate::{Allocator, BlobArray};
+
+/// Below this many elements per thread, splitting the work costs more than it saves.
+const MIN_SPLIT_LEN: usize = 1024;
+
+impl<A: Allocator> BlobArray<A> {
+    /// Parallel iterator over the elements, which are split into one contiguous run per available thread.
+    #[track_caller]
+    pub fn par_iter<T: Sync + 'static>(&self) -> ParIter<'_, T> {
+        ParIter { slice: self.as_slice() }
+    }
+
+    /// Marks every element as changed, like [`BlobArray::as_mut_slice`].
+    #[track_caller]
+    pub fn par_iter_mut<T: Send + 'static>(&mut self) -> ParIterMut<'_, T> {
+        ParIterMut { slice: self.as_mut_slice() }
+    }
+
+    /// Parallel iterator over `chunk_size` elements at a time, the last chunk may be shorter.
+    #[track_caller]
+    pub fn par_chunks_mut<T: Send + 'static>(&mut self, chunk_size: usize) -> ParChunksMut<'_, T> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        ParChunksMut {
+            slice: self.as_mut_slice(),
+            chunk_size,
+        }
+    }
+}
+
+fn thread_count(len: usize, min_len: usize) -> usize {
+    available_parallelism().min(len.div_ceil(min_len)).max(1)
+}
+
+fn available_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Worker threads shared by every parallel iterator, started on first use.
+/// The thread waiting on a batch of jobs helps running queued jobs,
+/// so nested parallel iterators can't starve the pool.
+struct Pool {
+    jobs: Mutex<VecDeque<Job>>,
+    queued: Condvar,
+}
+
+fn pool() -> &'static Pool {
+    static POOL: OnceLock<Pool> = OnceLock::new();
+
+    let mut started = false;
+    let pool = POOL.get_or_init(|| {
+        started = true;
+        Pool {
+            jobs: Mutex::new(VecDeque::new()),
+            queued: Condvar::new(),
+        }
+    });
+    if started {
+        // the calling thread takes part in every batch, so it doesn't need a worker of its own
+        for _ in 1..available_parallelism() {
+            std::thread::Builder::new()
+                .name("blob_array worker".to_string())
+                .spawn(|| pool.work())
+                .expect("failed to spawn a blob_array worker thread");
+        }
+    }
+    pool
+}
+
+impl Pool {
+    fn push(&self, job: Job) {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner).push_back(job);
+        self.queued.notify_one();
+    }
+
+    fn try_pop(&self) -> Option<Job> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner).pop_front()
+    }
+
+    fn work(&self) {
+        loop {
+            let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            let job = loop {
+                if let Some(job) = jobs.pop_front() {
+                    break job
+                }
+                jobs = self.queued.wait(jobs).unwrap_or_else(PoisonError::into_inner);
+            };
+            drop(jobs);
+            job();
+        }
+    }
+
+    /// Runs queued jobs until `latch` is done.
+    fn wait(&self, latch: &Latch) {
+        while !latch.is_done() {
+            match self.try_pop() {
+                Some(job) => job(),
+                None => latch.wait(),
+            }
+        }
+    }
+}
+
+struct Latch {
+    remaining: Mutex<usize>,
+    done: Condvar,
+}
+
+impl Latch {
+    fn count_down(&self) {
+        let mut remaining = self.remaining.lock().unwrap_or_else(PoisonError::into_inner);
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.done.notify_all();
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        *self.remaining.lock().unwrap_or_else(PoisonError::into_inner) == 0
+    }
+
+    fn wait(&self) {
+        let mut remaining = self.remaining.lock().unwrap_or_else(PoisonError::into_inner);
+        while *remaining != 0 {
+            remaining = self.done.wait(remaining).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+/// Runs `f` on every part, all but the last one on the pool, and returns the results in order.
+/// A panic on any part is resumed once all of them have finished.
+fn run<'a, P: Send + 'a, R: Send + 'a>(parts: impl Iterator<Item = P>, f: &'a (impl Fn(P) -> R + Sync)) -> Vec<R> {
+    struct Batch<R> {
+        results: Vec<Mutex<Option<thread::Result<R>>>>,
+        latch: Latch,
+    }
+
+    let mut parts = parts.collect::<Vec<_>>();
+    let last = parts.pop();
+    let batch = Arc::new(Batch {
+        results: parts.iter().map(|_| Mutex::new(None)).collect(),
+        latch: Latch {
+            remaining: Mutex::new(parts.len()),
+            done: Condvar::new(),
+        },
+    });
+
+    let pool = pool();
+    for (index, part) in parts.into_iter().enumerate() {
+        let batch = Arc::clone(&batch);
+        let job: Box<dyn FnOnce() + Send + 'a> = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(part)));
+            *batch.results[index].lock().unwrap_or_else(PoisonError::into_inner) = Some(result);
+            batch.latch.count_down();
+        });
+        // SAFETY: `run` neither returns nor unwinds before the latch says every job has finished,
+        // so whatever the jobs borrow outlives them
+        pool.push(unsafe { mem::transmute::<Box<dyn FnOnce() + Send + 'a>, Job>(job) });
+    }
+
+    let last = last.map(|part| panic::catch_unwind(AssertUnwindSafe(|| f(part))));
+    pool.wait(&batch.latch);
+
+    let batch = Arc::into_inner(batch).expect("every job has finished");
+    batch.results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap_or_else(PoisonError::into_inner).expect("every job has finished"))
+        .chain(last)
+        .map(|result| result.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+        .collect()
+}
+
+/// Runs `f` on `pieces` contiguous parts of `slice`, splitting only at multiples of `step`.
+fn split_mut<'a, T: Send, R: Send>(
+    slice: &'a mut [T],
+    pieces: usize,
+    step: usize,
+    f: &(impl Fn(&'a mut [T]) -> R + Sync),
+) -> Vec<R> {
+    let per_piece = slice.len().div_ceil(step).div_ceil(pieces.max(1)).max(1) * step;
+    if pieces <= 1 || per_piece >= slice.len() {
+        return vec![f(slice)]
+    }
+
+    run(slice.chunks_mut(per_piece), f)
+}
+
+fn split<'a, T: Sync, R: Send>(slice: &'a [T], pieces: usize, f: &(impl Fn(&'a [T]) -> R + Sync)) -> Vec<R> {
+    let per_piece = slice.len().div_ceil(pieces.max(1)).max(1);
+    if pieces <= 1 || per_piece >= slice.len() {
+        return vec![f(slice)]
+    }
+
+    run(slice.chunks(per_piece), f)
+}
+
+/// Iterator whose items are produced on several threads, one contiguous part of the array each.
+pub trait ParallelIterator: Sized {
+    type Item;
+
+    /// Folds every part with `fold`, each on its own thread, and returns the results in order.
+    fn drive<R: Send>(self, fold: &(dyn Fn(&mut dyn Iterator<Item = Self::Item>) -> R + Sync)) -> Vec<R>;
+
+    fn map<R, F: Fn(Self::Item) -> R + Sync>(self, f: F) -> Map<Self, F> {
+        Map { base: self, f }
+    }
+
+    fn filter<F: Fn(&Self::Item) -> bool + Sync>(self, f: F) -> Filter<Self, F> {
+        Filter { base: self, f }
+    }
+
+    fn for_each(self, f: impl Fn(Self::Item) + Sync) {
+        self.drive(&|iter| iter.for_each(&f));
+    }
+
+    /// Folds the items, first within each thread and then across threads.
+    /// `op` must be associative, and `identity` must not change the value it is combined with.
+    fn reduce(
+        self,
+        identity: impl Fn() -> Self::Item + Sync,
+        op: impl Fn(Self::Item, Self::Item) -> Self::Item + Sync,
+    ) -> Self::Item
+    where
+        Self::Item: Send,
+    {
+        self.drive(&|iter| iter.fold(identity(), &op)).into_iter().fold(identity(), &op)
+    }
+
+    fn sum<S: Send + Sum<Self::Item> + Sum<S>>(self) -> S {
+        self.drive(&|iter| iter.sum::<S>()).into_iter().sum()
+    }
+
+    fn count(self) -> usize {
+        self.drive(&|iter| iter.count()).into_iter().sum()
+    }
+}
+
+/// Shared parallel iterator returned by [`BlobArray::par_iter`].
+pub struct ParIter<'a, T> {
+    slice: &'a [T],
+}
+
+impl<'a, T: Sync> ParallelIterator for ParIter<'a, T> {
+    type Item = &'a T;
+
+    fn drive<R: Send>(self, fold: &(dyn Fn(&mut dyn Iterator<Item = &'a T>) -> R + Sync)) -> Vec<R> {
+        let pieces = thread_count(self.slice.len(), MIN_SPLIT_LEN);
+        split(self.slice, pieces, &|part| fold(&mut part.iter()))
+    }
+}
+
+/// Exclusive parallel iterator returned by [`BlobArray::par_iter_mut`].
+pub struct ParIterMut<'a, T> {
+    slice: &'a mut [T],
+}
+
+impl<'a, T: Send> ParallelIterator for ParIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn drive<R: Send>(self, fold: &(dyn Fn(&mut dyn Iterator<Item = &'a mut T>) -> R + Sync)) -> Vec<R> {
+        let pieces = thread_count(self.slice.len(), MIN_SPLIT_LEN);
+        split_mut(self.slice, pieces, 1, &|part| fold(&mut part.iter_mut()))
+    }
+}
+
+/// Exclusive parallel iterator over chunks, returned by [`BlobArray::par_chunks_mut`].
+pub struct ParChunksMut<'a, T> {
+    slice: &'a mut [T],
+    chunk_size: usize,
+}
+
+impl<'a, T: Send> ParallelIterator for ParChunksMut<'a, T> {
+    type Item = &'a mut [T];
+
+    fn drive<R: Send>(self, fold: &(dyn Fn(&mut dyn Iterator<Item = &'a mut [T]>) -> R + Sync)) -> Vec<R> {
+        let chunk_size = self.chunk_size;
+        let pieces = thread_count(self.slice.len().div_ceil(chunk_size), MIN_SPLIT_LEN.div_ceil(chunk_size));
+        split_mut(self.slice, pieces, chunk_size, &|part| fold(&mut part.chunks_mut(chunk_size)))
+    }
+}
+
+/// Returned by [`ParallelIterator::map`].
+pub struct Map<I, F> {
+    base: I,
+    f: F,
+}
+
+impl<I: ParallelIterator, R, F: Fn(I::Item) -> R + Sync> ParallelIterator for Map<I, F> {
+    type Item = R;
+
+    fn drive<S: Send>(self, fold: &(dyn Fn(&mut dyn Iterator<Item = R>) -> S + Sync)) -> Vec<S> {
+        let Self { base, f } = self;
+        base.drive(&|iter| fold(&mut iter.map(&f)))
+    }
+}
+
+/// Returned by [`ParallelIterator::filter`].
+pub struct Filter<I, F> {
+    base: I,
+    f: F,
+}
+
+impl<I: ParallelIterator, F: Fn(&I::Item) -> bool + Sync> ParallelIterator for Filter<I, F> {
+    type Item = I::Item;
+
+    fn drive<R: Send>(self, fold: &(dyn Fn(&mut dyn Iterator<Item = I::Item>) -> R + Sync)) -> Vec<R> {
+        let Self { base, f } = self;
+        base.drive(&|iter| fold(&mut iter.filter(&f)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const NUM: u64 = 100_000;
+
+    fn numbers() -> BlobArray {
+        let mut ba = BlobArray::new::<u64>(0);
+        ba.extend_from_iter(0..NUM);
+        ba
+    }
+
+    #[test]
+    fn par_iter() {
+        let ba = numbers();
+
+        let visited = AtomicUsize::new(0);
+        ba.par_iter::<u64>().for_each(|_| {
+            visited.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(visited.into_inner(), NUM as usize);
+
+        let sum = ba.par_iter::<u64>().map(|value| *value).reduce(|| 0, |a, b| a + b);
+        assert_eq!(sum, (0..NUM).sum());
+
+        let even = ba.par_iter::<u64>().filter(|value| *value % 2 == 0);
+        assert_eq!(even.map(|value| value / 2).sum::<u64>(), (0..NUM / 2).sum());
+        assert_eq!(ba.par_iter::<u64>().filter(|value| **value < 10).count(), 10);
+    }
+
+    #[test]
+    fn par_iter_mut() {
+        let mut ba = numbers();
+        ba.par_iter_mut::<u64>().for_each(|value| *value *= 2);
+        assert!(ba.iter::<u64>().enumerate().all(|(index, value)| *value == index as u64 * 2));
+    }
+
+    #[test]
+    fn par_chunks_mut() {
+        let mut ba = numbers();
+        let chunks = AtomicUsize::new(0);
+        ba.par_chunks_mut::<u64>(3).for_each(|chunk| {
+            assert!(chunk.len() == 3 || chunk.len() == (NUM % 3) as usize);
+            assert_eq!(chunk[0] % 3, 0);
+            chunks.fetch_add(1, Ordering::Relaxed);
+            chunk.fill(0);
+        });
+        assert_eq!(chunks.into_inner(), NUM.div_ceil(3) as usize);
+        assert!(ba.iter::<u64>().all(|value| *value == 0));
+    }
+
+    #[test]
+    fn nested_and_panic() {
+        let ba = numbers();
+        let slice = ba.as_slice::<u64>();
+        let total = ba.par_iter::<u64>()
+            .filter(|value| **value < 4)
+            .map(|_| ParIter { slice }.count())
+            .sum::<usize>();
+        assert_eq!(total, 4 * NUM as usize);
+
+        let result = std::panic::catch_unwind(|| {
+            ba.par_iter::<u64>().for_each(|value| assert!(*value != NUM / 2));
+        });
+        assert!(result.is_err());
+        assert_eq!(ba.par_iter::<u64>().count(), NUM as usize);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{Allocator, BlobArray, BlobArrayError};
+
+/// Plain old data: `Copy`, no padding, no drop fn, and every bit pattern is a valid value.
+/// Only these element types can be viewed as, or built from, raw bytes.
+///
+/// Has the same contract as `bytemuck::Pod`, and stands in for it until that crate is a dependency.
+///
+/// # Safety
+/// The implementing type must have no padding bytes and no invalid bit patterns,
+/// which rules out `bool`, `char`, references and most `#[repr(Rust)]` structs.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {$(
+        unsafe impl Pod for $ty {}
+    )*};
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+impl<A: Allocator> BlobArray<A> {
+    #[track_caller]
+    pub fn as_bytes<T: Pod>(&self) -> &[u8] {
+        let slice = self.as_slice::<T>();
+        unsafe { std::slice::from_raw_parts(slice.as_ptr().cast(), size_of_val(slice)) }
+    }
+
+    /// Marks every element as changed.
+    #[track_caller]
+    pub fn as_bytes_mut<T: Pod>(&mut self) -> &mut [u8] {
+        let slice = self.as_mut_slice::<T>();
+        unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr().cast(), size_of_val(slice)) }
+    }
+
+    /// Appends the elements encoded in `bytes`, which don't need to be aligned for `T`.
+    #[track_caller]
+    pub fn extend_from_bytes<T: Pod>(&mut self, bytes: &[u8]) {
+        self.try_extend_from_bytes::<T>(bytes).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_extend_from_bytes<T: Pod>(&mut self, bytes: &[u8]) -> Result<(), BlobArrayError> {
+        self.check_type::<T>()?;
+        let item_size = size_of::<T>();
+        if item_size == 0 || !bytes.len().is_multiple_of(item_size) {
+            return Err(BlobArrayError::ByteLength { len: bytes.len(), item_size })
+        }
+
+        let additional = bytes.len() / item_size;
+        self.try_reserve(additional)?;
+        unsafe {
+            let dst = self.block.as_ptr().add(self.len * item_size);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), dst, bytes.len());
+        }
+        self.len += additional;
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            (0..additional).for_each(|_| ticks.push());
+        }
+        Ok(())
+    }
+}
+
+impl BlobArray {
+    #[track_caller]
+    pub fn from_bytes<T: Pod>(bytes: &[u8]) -> Self {
+        Self::try_from_bytes::<T>(bytes).unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_from_bytes<T: Pod>(bytes: &[u8]) -> Result<Self, BlobArrayError> {
+        let mut array = Self::try_new::<T>(0)?;
+        array.try_extend_from_bytes::<T>(bytes)?;
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn byte_round_trip() {
+        let mut ba = BlobArray::new::<u32>(0);
+        ba.extend_from_iter([1u32, 2, 3]);
+        assert_eq!(ba.as_bytes::<u32>().len(), 12);
+        assert_eq!(&ba.as_bytes::<u32>()[..4], &1u32.to_ne_bytes());
+
+        ba.as_bytes_mut::<u32>()[..4].copy_from_slice(&7u32.to_ne_bytes());
+        assert_eq!(ba.get::<u32>(0), Some(&7));
+
+        // unaligned source
+        let mut bytes = vec![0u8];
+        bytes.extend_from_slice(ba.as_bytes::<u32>());
+        let copy = BlobArray::from_bytes::<u32>(&bytes[1..]);
+        assert_eq!(copy.as_slice::<u32>(), &[7, 2, 3]);
+
+        let mut copy = copy;
+        copy.extend_from_bytes::<u32>(&9u32.to_ne_bytes());
+        assert_eq!(copy.as_slice::<u32>(), &[7, 2, 3, 9]);
+    }
+
+    #[test]
+    fn byte_errors() {
+        assert_eq!(
+            BlobArray::try_from_bytes::<u32>(&[0; 5]).err(),
+            Some(BlobArrayError::ByteLength { len: 5, item_size: 4 }),
+        );
+
+        let mut ba = BlobArray::new::<u16>(0);
+        assert!(matches!(
+            ba.try_extend_from_bytes::<u32>(&[0; 4]),
+            Err(BlobArrayError::TypeMismatch { .. }),
+        ));
+        assert!(ba.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn as_bytes_wrong_type() {
+        let ba = BlobArray::new::<[f32; 2]>(0);
+        ba.as_bytes::<u64>();
+    }
+}
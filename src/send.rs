@@ -0,0 +1,170 @@
+use std::ops::Deref;
+
+use crate::{Allocator, BlobArray, Global, TypedBlobArray, TypedBlobArrayMut};
+
+impl BlobArray {
+    /// Like [`BlobArray::new`], but records that the elements are `Send`,
+    /// so the array can be turned into a [`SendBlobArray`].
+    #[track_caller]
+    pub fn new_send<T: Send + 'static>(capacity: usize) -> Self {
+        Self::new_send_in::<T>(capacity, Global)
+    }
+
+    /// Like [`BlobArray::new`], but records that the elements are `Send` and `Sync`,
+    /// so the array can be turned into a [`SendBlobArray`] or a [`SharedBlobArray`](crate::SharedBlobArray).
+    #[track_caller]
+    pub fn new_sync<T: Send + Sync + 'static>(capacity: usize) -> Self {
+        Self::new_sync_in::<T>(capacity, Global)
+    }
+}
+
+impl<A: Allocator> BlobArray<A> {
+    #[track_caller]
+    pub fn new_send_in<T: Send + 'static>(capacity: usize, alloc: A) -> Self {
+        let mut this = Self::new_in::<T>(capacity, alloc);
+        this.send = true;
+        this
+    }
+
+    #[track_caller]
+    pub fn new_sync_in<T: Send + Sync + 'static>(capacity: usize, alloc: A) -> Self {
+        let mut this = Self::new_send_in::<T>(capacity, alloc);
+        this.sync = true;
+        this
+    }
+
+    /// Whether the array was created for a type known to be `Send`.
+    pub fn is_send(&self) -> bool {
+        self.send
+    }
+
+    /// Whether the array was created for a type known to be `Sync`.
+    pub fn is_sync(&self) -> bool {
+        self.sync
+    }
+}
+
+/// A [`BlobArray`] of `Send` elements, which can itself be moved to another thread.
+///
+/// Only typed views are handed out mutably, so the array can't be swapped for one holding non-`Send` elements.
+pub struct SendBlobArray<A: Allocator = Global> {
+    array: BlobArray<A>,
+}
+
+// SAFETY: every way of building a `SendBlobArray` checks that the elements are `Send`
+unsafe impl<A: Allocator + Send> Send for SendBlobArray<A> {}
+
+impl SendBlobArray {
+    #[track_caller]
+    pub fn new<T: Send + 'static>(capacity: usize) -> Self {
+        Self { array: BlobArray::new_send::<T>(capacity) }
+    }
+}
+
+impl<A: Allocator> SendBlobArray<A> {
+    #[track_caller]
+    pub fn new_in<T: Send + 'static>(capacity: usize, alloc: A) -> Self {
+        Self { array: BlobArray::new_send_in::<T>(capacity, alloc) }
+    }
+
+    /// # Safety
+    /// The elements of `array` must be `Send`.
+    pub unsafe fn from_array_unchecked(array: BlobArray<A>) -> Self {
+        Self { array }
+    }
+
+    pub fn into_inner(self) -> BlobArray<A> {
+        self.array
+    }
+
+    #[track_caller]
+    pub fn typed<T: 'static>(&self) -> TypedBlobArray<'_, T, A> {
+        self.array.typed()
+    }
+
+    #[track_caller]
+    pub fn typed_mut<T: 'static>(&mut self) -> TypedBlobArrayMut<'_, T, A> {
+        self.array.typed_mut()
+    }
+
+    /// # Safety
+    /// The array must keep holding `Send` elements only.
+    pub unsafe fn as_mut_unchecked(&mut self) -> &mut BlobArray<A> {
+        &mut self.array
+    }
+}
+
+impl<A: Allocator> Deref for SendBlobArray<A> {
+    type Target = BlobArray<A>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.array
+    }
+}
+
+/// Succeeds for arrays created through [`BlobArray::new_send`] or [`BlobArray::new_sync`], giving the array back otherwise.
+impl<A: Allocator> TryFrom<BlobArray<A>> for SendBlobArray<A> {
+    type Error = BlobArray<A>;
+
+    fn try_from(array: BlobArray<A>) -> Result<Self, Self::Error> {
+        if array.is_send() {
+            Ok(Self { array })
+        } else {
+            Err(array)
+        }
+    }
+}
+
+impl<A: Allocator> From<SendBlobArray<A>> for BlobArray<A> {
+    fn from(send: SendBlobArray<A>) -> Self {
+        send.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SharedBlobArray;
+    use std::rc::Rc;
+
+    #[test]
+    fn flags() {
+        assert!(!BlobArray::new::<u32>(0).is_send());
+        assert!(BlobArray::new_send::<u32>(0).is_send());
+        assert!(!BlobArray::new_send::<std::cell::Cell<u32>>(0).is_sync());
+
+        let sync = BlobArray::new_sync::<u32>(0);
+        assert!(sync.is_send() && sync.is_sync());
+
+        let mut split = BlobArray::new_send::<u32>(4);
+        split.extend_from_iter(0..4u32);
+        assert!(split.split_off(2).is_send());
+    }
+
+    #[test]
+    fn move_across_threads() {
+        let mut array = SendBlobArray::new::<String>(0);
+        array.typed_mut::<String>().push("hello".to_string());
+
+        let array = std::thread::spawn(move || {
+            let mut array = array;
+            array.typed_mut::<String>().push("world".to_string());
+            array
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(array.as_slice::<String>(), ["hello", "world"]);
+    }
+
+    #[test]
+    fn runtime_checks() {
+        let not_send = BlobArray::new::<Rc<()>>(0);
+        let not_send = SendBlobArray::try_from(not_send).err().unwrap();
+        assert!(SharedBlobArray::try_from(not_send).is_err());
+
+        assert!(SendBlobArray::try_from(BlobArray::new_send::<u32>(0)).is_ok());
+        assert!(SharedBlobArray::try_from(BlobArray::new_send::<u32>(0)).is_err());
+        assert!(SharedBlobArray::try_from(BlobArray::new_sync::<u32>(0)).is_ok());
+    }
+}
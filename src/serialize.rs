@@ -0,0 +1,342 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+use crate::{Allocator, BlobArray};
+
+/// Element encoding used by [`BlobArray::serialize_as`] and the [`SerializeRegistry`].
+///
+/// Stands in for `serde::Serialize` until the crate is a dependency, and is named after it so the bound can be swapped.
+pub trait Serialize {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Stands in for `serde::de::DeserializeOwned`, see [`Serialize`].
+pub trait DeserializeOwned: Sized {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self>;
+}
+
+macro_rules! impl_le_bytes {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+        }
+
+        impl DeserializeOwned for $ty {
+            fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+                let mut bytes = [0; size_of::<$ty>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$ty>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+impl_le_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Serialize for usize {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as u64).serialize(writer)
+    }
+}
+
+impl DeserializeOwned for usize {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        usize::try_from(u64::deserialize(reader)?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl Serialize for isize {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as i64).serialize(writer)
+    }
+}
+
+impl DeserializeOwned for isize {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        isize::try_from(i64::deserialize(reader)?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl Serialize for bool {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as u8).serialize(writer)
+    }
+}
+
+impl DeserializeOwned for bool {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        match u8::deserialize(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid bool")),
+        }
+    }
+}
+
+impl Serialize for char {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as u32).serialize(writer)
+    }
+}
+
+impl DeserializeOwned for char {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        char::from_u32(u32::deserialize(reader)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid char"))
+    }
+}
+
+impl Serialize for str {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.len().serialize(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl Serialize for String {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.as_str().serialize(writer)
+    }
+}
+
+impl DeserializeOwned for String {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        let len = usize::deserialize(reader)?;
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into())
+        }
+        String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl<T: Serialize> Serialize for Vec<T> {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.len().serialize(writer)?;
+        self.iter().try_for_each(|value| value.serialize(writer))
+    }
+}
+
+impl<T: DeserializeOwned> DeserializeOwned for Vec<T> {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        let len = usize::deserialize(reader)?;
+        // the length is untrusted, so don't preallocate from it
+        (0..len).map(|_| T::deserialize(reader)).collect()
+    }
+}
+
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.is_some().serialize(writer)?;
+        match self {
+            Some(value) => value.serialize(writer),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> DeserializeOwned for Option<T> {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        match bool::deserialize(reader)? {
+            true => T::deserialize(reader).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: Serialize, const N: usize> Serialize for [T; N] {
+    fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.iter().try_for_each(|value| value.serialize(writer))
+    }
+}
+
+impl<T: DeserializeOwned, const N: usize> DeserializeOwned for [T; N] {
+    fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+        let values = (0..N).map(|_| T::deserialize(reader)).collect::<io::Result<Vec<_>>>()?;
+        Ok(values.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+impl<A: Allocator> BlobArray<A> {
+    /// Writes the length followed by every element.
+    #[track_caller]
+    pub fn serialize_as<T: Serialize + 'static>(&self, writer: &mut dyn Write) -> io::Result<()> {
+        serialize_slice(self.as_slice::<T>(), writer)
+    }
+}
+
+impl BlobArray {
+    /// Reads an array written by [`BlobArray::serialize_as`].
+    pub fn deserialize_as<T: DeserializeOwned + 'static>(reader: &mut dyn Read) -> io::Result<Self> {
+        let len = usize::deserialize(reader)?;
+        let mut array = BlobArray::new::<T>(0);
+        for _ in 0..len {
+            array.push(T::deserialize(reader)?);
+        }
+        Ok(array)
+    }
+}
+
+fn serialize_slice<T: Serialize>(slice: &[T], writer: &mut dyn Write) -> io::Result<()> {
+    slice.len().serialize(writer)?;
+    slice.iter().try_for_each(|value| value.serialize(writer))
+}
+
+struct Entry {
+    name: &'static str,
+    serialize: unsafe fn(*const u8, usize, &mut dyn Write) -> io::Result<()>,
+    deserialize: fn(&mut dyn Read) -> io::Result<BlobArray>,
+}
+
+/// # Safety
+/// `ptr` must point to `len` initialized values of `T`.
+unsafe fn serialize_erased<T: Serialize>(ptr: *const u8, len: usize, writer: &mut dyn Write) -> io::Result<()> {
+    let slice = unsafe { std::slice::from_raw_parts(ptr.cast::<T>(), len) };
+    serialize_slice(slice, writer)
+}
+
+/// Maps element types to their serialize / deserialize fns,
+/// so that arrays can be written and read back without knowing their static type.
+///
+/// Every array is tagged with the name it was registered under,
+/// the same name has to be registered when reading it back.
+#[derive(Default)]
+pub struct SerializeRegistry {
+    entries: HashMap<TypeId, Entry>,
+    names: HashMap<&'static str, TypeId>,
+}
+
+impl SerializeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Panics if `name` is already taken by another type.
+    #[track_caller]
+    pub fn register<T: Serialize + DeserializeOwned + 'static>(&mut self, name: &'static str) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        if let Some(existing) = self.names.get(name) {
+            assert!(*existing == type_id, "`{name}` is already registered for another type");
+        }
+
+        if let Some(old) = self.entries.insert(type_id, Entry {
+            name,
+            serialize: serialize_erased::<T>,
+            deserialize: BlobArray::deserialize_as::<T>,
+        }) {
+            self.names.remove(old.name);
+        }
+        self.names.insert(name, type_id);
+        self
+    }
+
+    pub fn is_registered(&self, type_id: TypeId) -> bool {
+        self.entries.contains_key(&type_id)
+    }
+
+    /// Fails with [`io::ErrorKind::Unsupported`] if the element type was erased or never registered.
+    pub fn serialize<A: Allocator>(&self, array: &BlobArray<A>, writer: &mut dyn Write) -> io::Result<()> {
+        let entry = array.type_id()
+            .and_then(|type_id| self.entries.get(&type_id))
+            .ok_or_else(|| io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("`{}` is not registered for serialization", array.type_name()),
+            ))?;
+
+        entry.name.serialize(writer)?;
+        // SAFETY: the entry was looked up by the array's own TypeId
+        unsafe { (entry.serialize)(array.as_ptr(), array.len(), writer) }
+    }
+
+    /// Fails with [`io::ErrorKind::InvalidData`] if the stored name was never registered.
+    pub fn deserialize(&self, reader: &mut dyn Read) -> io::Result<BlobArray> {
+        let name = String::deserialize(reader)?;
+        let entry = self.names.get(name.as_str())
+            .and_then(|type_id| self.entries.get(type_id))
+            .ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("`{name}` is not registered for deserialization"),
+            ))?;
+
+        (entry.deserialize)(reader)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Obj {
+        name: String,
+        age: u32,
+    }
+
+    impl Serialize for Obj {
+        fn serialize(&self, writer: &mut dyn Write) -> io::Result<()> {
+            self.name.serialize(writer)?;
+            self.age.serialize(writer)
+        }
+    }
+
+    impl DeserializeOwned for Obj {
+        fn deserialize(reader: &mut dyn Read) -> io::Result<Self> {
+            Ok(Self {
+                name: String::deserialize(reader)?,
+                age: u32::deserialize(reader)?,
+            })
+        }
+    }
+
+    fn objs() -> BlobArray {
+        let mut ba = BlobArray::new::<Obj>(0);
+        ba.push(Obj { name: "Ochi".to_string(), age: 1 });
+        ba.push(Obj { name: "Nana".to_string(), age: 2 });
+        ba
+    }
+
+    #[test]
+    fn typed_round_trip() {
+        let ba = objs();
+        let mut bytes = Vec::new();
+        ba.serialize_as::<Obj>(&mut bytes).unwrap();
+
+        let back = BlobArray::deserialize_as::<Obj>(&mut bytes.as_slice()).unwrap();
+        assert_eq!(back.as_slice::<Obj>(), ba.as_slice::<Obj>());
+
+        let err = BlobArray::deserialize_as::<Obj>(&mut &bytes[..bytes.len() - 1]).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn registry_round_trip() {
+        let mut registry = SerializeRegistry::new();
+        registry.register::<Obj>("obj").register::<[f32; 2]>("vec2");
+
+        let mut positions = BlobArray::new::<[f32; 2]>(0);
+        positions.extend_from_iter([[0.0f32, 1.0], [2.0, 3.0]]);
+
+        let mut bytes = Vec::new();
+        registry.serialize(&objs(), &mut bytes).unwrap();
+        registry.serialize(&positions, &mut bytes).unwrap();
+
+        let mut reader = bytes.as_slice();
+        let first = registry.deserialize(&mut reader).unwrap();
+        let second = registry.deserialize(&mut reader).unwrap();
+        assert!(reader.is_empty());
+
+        assert_eq!(first.as_slice::<Obj>(), objs().as_slice::<Obj>());
+        assert_eq!(second.as_slice::<[f32; 2]>(), positions.as_slice::<[f32; 2]>());
+
+        let unknown = BlobArray::new::<u8>(0);
+        let err = registry.serialize(&unknown, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+
+        let err = SerializeRegistry::new().deserialize(&mut bytes.as_slice()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
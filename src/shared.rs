@@ -0,0 +1,226 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{Allocator, BlobArray, BlobArrayError, Global, TypedBlobArrayMut};
+
+const WRITING: usize = usize::MAX;
+
+/// A [`BlobArray`] which can be shared between threads, handing out its elements through runtime checked
+/// borrows instead of [`BlobArray::get_cell`]. Any number of [`BlobRef`] or a single [`BlobRefMut`] can be alive at once.
+///
+/// The inner array is only reachable mutably through a typed view, which can't swap it for one with another
+/// element type. Mutations through a [`BlobRefMut`] aren't seen by change detection.
+pub struct SharedBlobArray<A: Allocator = Global> {
+    array: BlobArray<A>,
+    borrow: AtomicUsize,
+}
+
+// SAFETY: the constructors only accept arrays of `Send + Sync` elements,
+// and the borrow flag rules out data races between shared and exclusive borrows
+unsafe impl<A: Allocator + Send> Send for SharedBlobArray<A> {}
+unsafe impl<A: Allocator + Sync> Sync for SharedBlobArray<A> {}
+
+impl SharedBlobArray {
+    #[track_caller]
+    pub fn new<T: Send + Sync + 'static>(capacity: usize) -> Self {
+        unsafe { Self::from_array(BlobArray::new_sync::<T>(capacity)) }
+    }
+}
+
+impl<A: Allocator> SharedBlobArray<A> {
+    /// # Safety
+    /// The elements of `array` must be `Send + Sync`.
+    pub unsafe fn from_array(array: BlobArray<A>) -> Self {
+        Self {
+            array,
+            borrow: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn into_inner(self) -> BlobArray<A> {
+        self.array
+    }
+
+    /// Exclusive access doesn't need the borrow flag, as no borrow can be alive while `self` is mutably borrowed.
+    #[track_caller]
+    pub fn typed_mut<T: 'static>(&mut self) -> TypedBlobArrayMut<'_, T, A> {
+        self.array.typed_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    #[track_caller]
+    pub fn borrow<T: 'static>(&self) -> BlobRef<'_, T> {
+        self.try_borrow().unwrap_or_else(|err| err.handle())
+    }
+
+    #[track_caller]
+    pub fn borrow_mut<T: 'static>(&self) -> BlobRefMut<'_, T> {
+        self.try_borrow_mut().unwrap_or_else(|err| err.handle())
+    }
+
+    pub fn try_borrow<T: 'static>(&self) -> Result<BlobRef<'_, T>, BlobArrayError> {
+        self.array.check_type::<T>()?;
+
+        let mut current = self.borrow.load(Ordering::Relaxed);
+        loop {
+            if current == WRITING {
+                return Err(BlobArrayError::BorrowConflict)
+            }
+            assert!(current < WRITING - 1, "too many shared borrows of a SharedBlobArray");
+
+            match self.borrow.compare_exchange_weak(current, current + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+
+        let slice = unsafe { std::slice::from_raw_parts(self.array.block.as_ptr().cast::<T>(), self.array.len) };
+        Ok(BlobRef {
+            slice,
+            borrow: &self.borrow,
+        })
+    }
+
+    pub fn try_borrow_mut<T: 'static>(&self) -> Result<BlobRefMut<'_, T>, BlobArrayError> {
+        self.array.check_type::<T>()?;
+
+        self.borrow
+            .compare_exchange(0, WRITING, Ordering::Acquire, Ordering::Relaxed)
+            .map_err(|_| BlobArrayError::BorrowConflict)?;
+
+        // SAFETY: the flag guarantees this is the only borrow, and the heap block isn't owned by `&self`
+        let slice = unsafe { std::slice::from_raw_parts_mut(self.array.block.as_ptr().cast::<T>(), self.array.len) };
+        Ok(BlobRefMut {
+            slice,
+            borrow: &self.borrow,
+        })
+    }
+}
+
+/// Succeeds for arrays created through [`BlobArray::new_sync`], giving the array back otherwise.
+impl<A: Allocator> TryFrom<BlobArray<A>> for SharedBlobArray<A> {
+    type Error = BlobArray<A>;
+
+    fn try_from(array: BlobArray<A>) -> Result<Self, Self::Error> {
+        if array.is_send() && array.is_sync() {
+            Ok(unsafe { Self::from_array(array) })
+        } else {
+            Err(array)
+        }
+    }
+}
+
+impl<A: Allocator> From<SharedBlobArray<A>> for BlobArray<A> {
+    fn from(shared: SharedBlobArray<A>) -> Self {
+        shared.into_inner()
+    }
+}
+
+/// Shared borrow of a [`SharedBlobArray`]'s elements.
+pub struct BlobRef<'a, T> {
+    slice: &'a [T],
+    borrow: &'a AtomicUsize,
+}
+
+impl<T> Deref for BlobRef<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.slice
+    }
+}
+
+impl<T> Drop for BlobRef<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Exclusive borrow of a [`SharedBlobArray`]'s elements.
+pub struct BlobRefMut<'a, T> {
+    slice: &'a mut [T],
+    borrow: &'a AtomicUsize,
+}
+
+impl<T> Deref for BlobRefMut<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.slice
+    }
+}
+
+impl<T> DerefMut for BlobRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.slice
+    }
+}
+
+impl<T> Drop for BlobRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.store(0, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn borrow_rules() {
+        let mut shared = SharedBlobArray::new::<u32>(0);
+        shared.typed_mut::<u32>().extend(0..4);
+
+        let first = shared.borrow::<u32>();
+        let second = shared.borrow::<u32>();
+        assert_eq!(*first, *second);
+        assert_eq!(shared.try_borrow_mut::<u32>().err(), Some(BlobArrayError::BorrowConflict));
+        drop((first, second));
+
+        let mut exclusive = shared.borrow_mut::<u32>();
+        exclusive.iter_mut().for_each(|value| *value *= 2);
+        assert_eq!(shared.try_borrow::<u32>().err(), Some(BlobArrayError::BorrowConflict));
+        assert_eq!(shared.try_borrow_mut::<u32>().err(), Some(BlobArrayError::BorrowConflict));
+        drop(exclusive);
+
+        assert_eq!(*shared.borrow::<u32>(), [0, 2, 4, 6]);
+        assert!(matches!(shared.try_borrow::<i32>(), Err(BlobArrayError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn conflicting_borrow_panics() {
+        let shared = SharedBlobArray::new::<u32>(0);
+        let _reader = shared.borrow::<u32>();
+        shared.borrow_mut::<u32>();
+    }
+
+    #[test]
+    fn across_threads() {
+        let mut shared = SharedBlobArray::new::<u64>(0);
+        shared.typed_mut::<u64>().extend(0..1000);
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    loop {
+                        if let Ok(mut values) = shared.try_borrow_mut::<u64>() {
+                            values.iter_mut().for_each(|value| *value += 1);
+                            break
+                        }
+                        std::thread::yield_now();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(shared.borrow::<u64>().iter().sum::<u64>(), (4..1004).sum());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{Allocator, BlobArray, Global, Iter, IterMut};
+
+/// A stable reference to an element of a [`SlotBlobArray`].
+/// It stays valid while the element is alive, no matter how other elements are moved around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    pub index: u32,
+    pub generation: u32,
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    generation: u32,
+    dense: Option<usize>,
+}
+
+/// A [`BlobArray`] addressed through generational [`Handle`]s.
+///
+/// Elements stay packed in the dense array, removal swaps the last one into the hole,
+/// and every handle goes through a slot that's updated when its element moves.
+/// Removing an element bumps its slot's generation, so stale handles are rejected.
+pub struct SlotBlobArray<A: Allocator = Global> {
+    dense: BlobArray<A>,
+    /// Slot index of every dense element.
+    dense_to_slot: Vec<u32>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl SlotBlobArray {
+    #[track_caller]
+    pub fn new<T: 'static>(capacity: usize) -> Self {
+        Self::new_in::<T>(capacity, Global)
+    }
+}
+
+impl<A: Allocator> SlotBlobArray<A> {
+    #[track_caller]
+    pub fn new_in<T: 'static>(capacity: usize, alloc: A) -> Self {
+        Self {
+            dense: BlobArray::new_in::<T>(capacity, alloc),
+            dense_to_slot: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    /// The packed element storage, in dense order.
+    pub fn as_blob_array(&self) -> &BlobArray<A> {
+        &self.dense
+    }
+
+    #[track_caller]
+    pub fn insert<T: 'static>(&mut self, value: T) -> Handle {
+        self.dense.push(value);
+        let dense = self.dense.len() - 1;
+
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index as usize].dense = Some(dense);
+                index
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("SlotBlobArray slot count overflowed u32");
+                self.slots.push(Slot { generation: 0, dense: Some(dense) });
+                index
+            }
+        };
+
+        self.dense_to_slot.push(index);
+        Handle { index, generation: self.slots[index as usize].generation }
+    }
+
+    /// Dense index of the element behind `handle`, or `None` if the handle is stale.
+    pub fn dense_index(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.index as usize)?;
+        if slot.generation != handle.generation { return None }
+        slot.dense
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.dense_index(handle).is_some()
+    }
+
+    /// Handle of the element at `dense`, e.g. while walking [`SlotBlobArray::as_slice`].
+    pub fn handle_at(&self, dense: usize) -> Option<Handle> {
+        let index = *self.dense_to_slot.get(dense)?;
+        Some(Handle { index, generation: self.slots[index as usize].generation })
+    }
+
+    #[track_caller]
+    pub fn get<T: 'static>(&self, handle: Handle) -> Option<&T> {
+        self.dense.get(self.dense_index(handle)?)
+    }
+
+    #[track_caller]
+    pub fn get_mut<T: 'static>(&mut self, handle: Handle) -> Option<&mut T> {
+        let dense = self.dense_index(handle)?;
+        self.dense.get_mut(dense)
+    }
+
+    #[track_caller]
+    pub fn remove<T: 'static>(&mut self, handle: Handle) -> Option<T> {
+        let dense = self.dense_index(handle)?;
+        let value = self.dense.swap_remove(dense);
+        self.release(handle.index, dense);
+        value
+    }
+
+    /// Returns whether `handle` was alive.
+    pub fn remove_and_drop(&mut self, handle: Handle) -> bool {
+        let Some(dense) = self.dense_index(handle) else { return false };
+        // release first, so a panicking destructor can't leave the slots pointing at a removed element
+        self.release(handle.index, dense);
+        self.dense.swap_remove_and_drop(dense);
+        true
+    }
+
+    /// Frees the slot of the element at `dense`, which is being swap removed.
+    fn release(&mut self, index: u32, dense: usize) {
+        self.dense_to_slot.swap_remove(dense);
+        if let Some(&moved) = self.dense_to_slot.get(dense) {
+            self.slots[moved as usize].dense = Some(dense);
+        }
+
+        let slot = &mut self.slots[index as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.dense = None;
+        self.free.push(index);
+    }
+
+    /// Invalidates every handle.
+    pub fn clear(&mut self) {
+        for &index in &self.dense_to_slot {
+            let slot = &mut self.slots[index as usize];
+            slot.generation = slot.generation.wrapping_add(1);
+            slot.dense = None;
+            self.free.push(index);
+        }
+        self.dense_to_slot.clear();
+        self.dense.clear();
+    }
+
+    #[track_caller]
+    pub fn as_slice<T: 'static>(&self) -> &[T] {
+        self.dense.as_slice()
+    }
+
+    #[track_caller]
+    pub fn as_mut_slice<T: 'static>(&mut self) -> &mut [T] {
+        self.dense.as_mut_slice()
+    }
+
+    #[track_caller]
+    pub fn iter<T: 'static>(&self) -> Iter<'_, T> {
+        self.dense.iter()
+    }
+
+    #[track_caller]
+    pub fn iter_mut<T: 'static>(&mut self) -> IterMut<'_, T> {
+        self.dense.iter_mut()
+    }
+
+    /// Handles of every element, in dense order.
+    pub fn handles(&self) -> impl Iterator<Item = Handle> + '_ {
+        (0..self.len()).filter_map(|dense| self.handle_at(dense))
+    }
+}
+
+impl<A: Allocator> std::fmt::Debug for SlotBlobArray<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlotBlobArray")
+            .field("type_name", &self.dense.type_name())
+            .field("len", &self.len())
+            .field("slots", &self.slots.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stale_handles() {
+        let mut slots = SlotBlobArray::new::<String>(0);
+        let a = slots.insert("a".to_string());
+        let b = slots.insert("b".to_string());
+        let c = slots.insert("c".to_string());
+
+        assert_eq!(slots.remove::<String>(a).as_deref(), Some("a"));
+        assert_eq!(slots.remove::<String>(a), None);
+        assert!(!slots.contains(a));
+
+        // `c` was swapped into `a`'s place but its handle still resolves
+        assert_eq!(slots.get::<String>(c).map(String::as_str), Some("c"));
+        assert_eq!(slots.get::<String>(b).map(String::as_str), Some("b"));
+        assert_eq!(slots.as_slice::<String>(), &["c".to_string(), "b".to_string()]);
+
+        // the slot is reused with a new generation
+        let d = slots.insert("d".to_string());
+        assert_eq!(d.index, a.index);
+        assert_ne!(d.generation, a.generation);
+        assert_eq!(slots.get::<String>(a), None);
+        assert_eq!(slots.get::<String>(d).map(String::as_str), Some("d"));
+
+        slots.get_mut::<String>(b).unwrap().push('!');
+        assert_eq!(slots.handles().collect::<Vec<_>>(), [c, b, d]);
+        assert!(slots.remove_and_drop(b));
+        assert!(!slots.remove_and_drop(b));
+        assert_eq!(slots.iter::<String>().cloned().collect::<Vec<_>>(), ["c", "d"]);
+
+        slots.clear();
+        assert!(slots.is_empty());
+        assert!(!slots.contains(c) && !slots.contains(d));
+    }
+
+    #[test]
+    fn churn() {
+        let mut slots = SlotBlobArray::new::<usize>(0);
+        let mut alive = Vec::new();
+        for i in 0..1000usize {
+            alive.push((slots.insert(i), i));
+            if i % 3 == 0 {
+                let (handle, value) = alive.swap_remove(i % alive.len());
+                assert_eq!(slots.remove::<usize>(handle), Some(value));
+            }
+        }
+
+        assert_eq!(slots.len(), alive.len());
+        for (handle, value) in alive {
+            assert_eq!(slots.get::<usize>(handle), Some(&value));
+        }
+    }
+}
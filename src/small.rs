@@ -0,0 +1,295 @@
+use std::mem::MaybeUninit;
+
+use crate::{BlobArray, Iter, IterMut};
+
+/// Alignment of the inline buffer, elements aligned to more than this always live on the heap.
+const INLINE_ALIGN: usize = 16;
+
+#[repr(C, align(16))]
+struct InlineBuffer<const N_BYTES: usize>([MaybeUninit<u8>; N_BYTES]);
+
+/// A [`BlobArray`] which keeps up to `N_BYTES` worth of elements inline, and only allocates once they don't fit.
+///
+/// After spilling, the elements live in a regular `BlobArray` and grow the same way.
+/// Elements aligned to more than 16 bytes don't fit the inline buffer, so they are always stored on the heap.
+pub struct SmallBlobArray<const N_BYTES: usize> {
+    inline: InlineBuffer<N_BYTES>,
+    inline_len: usize,
+    /// Carries the element type, and holds the elements once spilled.
+    array: BlobArray,
+}
+
+impl<const N_BYTES: usize> Drop for SmallBlobArray<N_BYTES> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<const N_BYTES: usize> SmallBlobArray<N_BYTES> {
+    pub fn new<T: 'static>() -> Self {
+        Self {
+            inline: InlineBuffer([MaybeUninit::uninit(); N_BYTES]),
+            inline_len: 0,
+            // an empty array doesn't allocate
+            array: BlobArray::new::<T>(0),
+        }
+    }
+
+    /// How many elements fit inline, zero if the element alignment exceeds the inline buffer's.
+    pub fn inline_capacity(&self) -> usize {
+        let layout = self.array.item_layout();
+        if layout.size() == 0 || layout.align() > INLINE_ALIGN { return 0 }
+        N_BYTES / layout.size()
+    }
+
+    /// Whether the elements moved to the heap. Zero sized elements never need the inline buffer.
+    pub fn spilled(&self) -> bool {
+        self.array.capacity() > 0
+    }
+
+    pub fn len(&self) -> usize {
+        if self.spilled() { self.array.len() } else { self.inline_len }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        if self.spilled() { self.array.capacity() } else { self.inline_capacity() }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.array.type_name()
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.array.is::<T>()
+    }
+
+    fn inline_ptr(&self, index: usize) -> *const u8 {
+        self.inline.0.as_ptr().cast::<u8>().wrapping_add(index * self.array.item_layout().size())
+    }
+
+    fn inline_mut_ptr(&mut self, index: usize) -> *mut u8 {
+        let size = self.array.item_layout().size();
+        self.inline.0.as_mut_ptr().cast::<u8>().wrapping_add(index * size)
+    }
+
+    /// Moves the inline elements into the heap array, with room for `additional` more.
+    #[track_caller]
+    fn spill(&mut self, additional: usize) {
+        self.array.reserve(self.inline_len + additional);
+        let len = std::mem::replace(&mut self.inline_len, 0);
+        for index in 0..len {
+            unsafe { self.array.push_raw(self.inline_ptr(index)) }
+        }
+    }
+
+    #[track_caller]
+    pub fn reserve(&mut self, additional: usize) {
+        if self.spilled() {
+            self.array.reserve(additional)
+        } else if self.inline_len + additional > self.inline_capacity() {
+            self.spill(additional)
+        }
+    }
+
+    #[track_caller]
+    pub fn push<T: 'static>(&mut self, value: T) {
+        self.array.assert_type::<T>();
+        if self.spilled() {
+            return self.array.push(value)
+        }
+
+        if self.inline_len == self.inline_capacity() {
+            self.spill(1);
+            return self.array.push(value)
+        }
+
+        unsafe { self.inline_mut_ptr(self.inline_len).cast::<T>().write(value) };
+        self.inline_len += 1;
+    }
+
+    #[track_caller]
+    pub fn as_slice<T: 'static>(&self) -> &[T] {
+        if self.spilled() {
+            return self.array.as_slice()
+        }
+
+        self.array.assert_type::<T>();
+        unsafe { std::slice::from_raw_parts(self.inline_ptr(0).cast::<T>(), self.inline_len) }
+    }
+
+    #[track_caller]
+    pub fn as_mut_slice<T: 'static>(&mut self) -> &mut [T] {
+        if self.spilled() {
+            return self.array.as_mut_slice()
+        }
+
+        self.array.assert_type::<T>();
+        unsafe { std::slice::from_raw_parts_mut(self.inline_mut_ptr(0).cast::<T>(), self.inline_len) }
+    }
+
+    #[track_caller]
+    pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    #[track_caller]
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    #[track_caller]
+    pub fn iter<T: 'static>(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slice())
+    }
+
+    #[track_caller]
+    pub fn iter_mut<T: 'static>(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.as_mut_slice(), None)
+    }
+
+    #[track_caller]
+    pub fn swap_remove<T: 'static>(&mut self, index: usize) -> Option<T> {
+        if self.spilled() {
+            return self.array.swap_remove(index)
+        }
+
+        let slice = self.as_mut_slice::<T>();
+        if index >= slice.len() { return None }
+
+        slice.swap(index, slice.len() - 1);
+        self.pop()
+    }
+
+    #[track_caller]
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        if self.spilled() {
+            return self.array.pop()
+        }
+
+        self.array.assert_type::<T>();
+        if self.inline_len == 0 { return None }
+
+        self.inline_len -= 1;
+        Some(unsafe { self.inline_ptr(self.inline_len).cast::<T>().read() })
+    }
+
+    pub fn clear(&mut self) {
+        if self.spilled() {
+            return self.array.clear()
+        }
+
+        // forget the elements first, so a panicking destructor leaks the rest instead of dropping them twice
+        let len = std::mem::replace(&mut self.inline_len, 0);
+        if let Some(drop) = self.array.drop {
+            for index in 0..len {
+                unsafe { drop(self.inline_mut_ptr(index)) }
+            }
+        }
+    }
+
+    /// Moves the elements into a heap allocated [`BlobArray`].
+    #[track_caller]
+    pub fn into_blob_array(mut self) -> BlobArray {
+        if !self.spilled() && self.inline_len > 0 {
+            self.spill(0);
+        }
+        // leave an empty array behind for `Drop`
+        let empty = unsafe { BlobArray::from_layout(self.array.item_layout(), None, None, 0) };
+        std::mem::replace(&mut self.array, empty)
+    }
+}
+
+impl<const N_BYTES: usize> std::fmt::Debug for SmallBlobArray<N_BYTES> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmallBlobArray")
+            .field("type_name", &self.type_name())
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .field("spilled", &self.spilled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn inline_then_spill() {
+        let mut small = SmallBlobArray::<64>::new::<String>();
+        assert_eq!(small.inline_capacity(), 64 / size_of::<String>());
+
+        for i in 0..small.inline_capacity() {
+            small.push(i.to_string());
+        }
+        assert!(!small.spilled());
+        assert_eq!(small.get::<String>(1).map(String::as_str), Some("1"));
+        assert_eq!(small.swap_remove::<String>(0).as_deref(), Some("0"));
+        small.push("spill".to_string());
+        small.push("over".to_string());
+
+        assert!(small.spilled());
+        assert_eq!(small.len(), small.inline_capacity() + 1);
+        small.iter_mut::<String>().for_each(|value| value.push('!'));
+        assert_eq!(small.iter::<String>().next_back().map(String::as_str), Some("over!"));
+        assert_eq!(small.pop::<String>().as_deref(), Some("over!"));
+
+        let array = small.into_blob_array();
+        assert_eq!(array.get::<String>(0).map(String::as_str), Some("1!"));
+    }
+
+    #[test]
+    fn drops_inline_elements() {
+        let rc = Rc::new(Cell::new(0));
+        {
+            let mut small = SmallBlobArray::<32>::new::<Rc<Cell<i32>>>();
+            small.push(rc.clone());
+            small.push(rc.clone());
+            assert!(!small.spilled());
+            assert_eq!(Rc::strong_count(&rc), 3);
+
+            let inline = small.into_blob_array();
+            assert_eq!(inline.len(), 2);
+        }
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut small = SmallBlobArray::<32>::new::<Rc<Cell<i32>>>();
+        small.push(rc.clone());
+        small.clear();
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn overaligned_and_zst() {
+        #[derive(Clone, Copy)]
+        #[repr(align(64))]
+        struct Aligned(u8);
+
+        let mut small = SmallBlobArray::<256>::new::<Aligned>();
+        assert_eq!(small.inline_capacity(), 0);
+        small.push(Aligned(7));
+        assert!(small.spilled());
+        assert_eq!(small.get::<Aligned>(0).map(|value| value.0), Some(7));
+        assert_eq!(small.as_slice::<Aligned>().as_ptr() as usize % 64, 0);
+
+        let mut zst = SmallBlobArray::<8>::new::<()>();
+        for _ in 0..100 {
+            zst.push(());
+        }
+        assert_eq!(zst.len(), 100);
+        assert_eq!(zst.swap_remove::<()>(5), Some(()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_type() {
+        let mut small = SmallBlobArray::<16>::new::<u32>();
+        small.push(1u64);
+    }
+}
@@ -0,0 +1,259 @@
+use std::ptr::NonNull;
+
+use crate::{Allocator, BlobArray, Global};
+
+const PAGE_SIZE: usize = 1024;
+const EMPTY: usize = usize::MAX;
+
+type Page = Box<[usize; PAGE_SIZE]>;
+
+/// Sparse set storage for components: the values and their entities are kept in two parallel dense arrays,
+/// and a paged sparse index maps every entity to its dense position.
+/// Pages are only allocated once an entity falls into them, so large entity ids stay cheap.
+///
+/// The typed methods check `T` like [`BlobArray`]'s do, and the `*_raw` methods allow using
+/// a set created with [`BlobSparseSet::from_array`] for components only known at runtime.
+pub struct BlobSparseSet<A: Allocator = Global> {
+    dense: BlobArray<A>,
+    entities: Vec<usize>,
+    sparse: Vec<Option<Page>>,
+}
+
+impl BlobSparseSet {
+    #[track_caller]
+    pub fn new<T: 'static>() -> Self {
+        Self::from_array(BlobArray::new::<T>(0))
+    }
+}
+
+impl<A: Allocator> BlobSparseSet<A> {
+    /// Uses `array` as the dense value storage, e.g. one built with [`BlobArray::from_layout`]
+    /// for a type erased component. Panics if `array` isn't empty.
+    #[track_caller]
+    pub fn from_array(array: BlobArray<A>) -> Self {
+        assert!(array.is_empty(), "BlobSparseSet must be created from an empty array");
+        Self {
+            dense: array,
+            entities: Vec::new(),
+            sparse: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn as_blob_array(&self) -> &BlobArray<A> {
+        &self.dense
+    }
+
+    /// Entities in dense order, parallel to [`BlobSparseSet::as_slice`].
+    pub fn entities(&self) -> &[usize] {
+        &self.entities
+    }
+
+    pub fn dense_index(&self, entity: usize) -> Option<usize> {
+        let page = self.sparse.get(entity / PAGE_SIZE)?.as_ref()?;
+        match page[entity % PAGE_SIZE] {
+            EMPTY => None,
+            dense => Some(dense),
+        }
+    }
+
+    pub fn contains(&self, entity: usize) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    fn set_sparse(&mut self, entity: usize, dense: usize) {
+        let page = entity / PAGE_SIZE;
+        if page >= self.sparse.len() {
+            self.sparse.resize_with(page + 1, || None);
+        }
+        self.sparse[page].get_or_insert_with(|| Box::new([EMPTY; PAGE_SIZE]))[entity % PAGE_SIZE] = dense;
+    }
+
+    /// Inserts the component of `entity`, returning the previous one if there was any.
+    #[track_caller]
+    pub fn insert<T: 'static>(&mut self, entity: usize, value: T) -> Option<T> {
+        assert!(entity != EMPTY, "entity id {EMPTY} is reserved");
+        match self.dense_index(entity) {
+            Some(dense) => self.dense.get_mut(dense).map(|old| std::mem::replace(old, value)),
+            None => {
+                self.dense.push(value);
+                self.push_entity(entity);
+                None
+            }
+        }
+    }
+
+    /// Moves the value at `value` into the set, dropping the previous component of `entity` if there was any.
+    ///
+    /// # Safety
+    /// See [`BlobArray::push_raw`].
+    #[track_caller]
+    pub unsafe fn insert_raw(&mut self, entity: usize, value: *const u8) {
+        assert!(entity != EMPTY, "entity id {EMPTY} is reserved");
+        unsafe { self.dense.push_raw(value) };
+        match self.dense_index(entity) {
+            // the new value is swapped into the old one's place
+            Some(dense) => self.dense.swap_remove_and_drop(dense),
+            None => self.push_entity(entity),
+        }
+    }
+
+    fn push_entity(&mut self, entity: usize) {
+        self.entities.push(entity);
+        self.set_sparse(entity, self.entities.len() - 1);
+    }
+
+    #[track_caller]
+    pub fn remove<T: 'static>(&mut self, entity: usize) -> Option<T> {
+        let dense = self.dense_index(entity)?;
+        let value = self.dense.swap_remove(dense);
+        self.release(entity, dense);
+        value
+    }
+
+    /// Returns whether `entity` had a component.
+    pub fn remove_and_drop(&mut self, entity: usize) -> bool {
+        let Some(dense) = self.dense_index(entity) else { return false };
+        // release first, so a panicking destructor can't leave the index pointing at a removed value
+        self.release(entity, dense);
+        self.dense.swap_remove_and_drop(dense);
+        true
+    }
+
+    /// Moves the component of `entity` to `out`, which then owns it.
+    ///
+    /// # Safety
+    /// See [`BlobArray::swap_remove_raw`].
+    pub unsafe fn remove_raw(&mut self, entity: usize, out: *mut u8) -> bool {
+        let Some(dense) = self.dense_index(entity) else { return false };
+        unsafe { self.dense.swap_remove_raw(dense, out) };
+        self.release(entity, dense);
+        true
+    }
+
+    /// Mirrors a swap remove of `dense` in the entity array and the sparse index.
+    fn release(&mut self, entity: usize, dense: usize) {
+        self.entities.swap_remove(dense);
+        if let Some(&moved) = self.entities.get(dense) {
+            self.set_sparse(moved, dense);
+        }
+        self.set_sparse(entity, EMPTY);
+    }
+
+    #[track_caller]
+    pub fn get<T: 'static>(&self, entity: usize) -> Option<&T> {
+        self.dense.get(self.dense_index(entity)?)
+    }
+
+    #[track_caller]
+    pub fn get_mut<T: 'static>(&mut self, entity: usize) -> Option<&mut T> {
+        let dense = self.dense_index(entity)?;
+        self.dense.get_mut(dense)
+    }
+
+    pub fn get_ptr(&self, entity: usize) -> Option<NonNull<u8>> {
+        Some(self.dense.get_ptr(self.dense_index(entity)?))
+    }
+
+    #[track_caller]
+    pub fn as_slice<T: 'static>(&self) -> &[T] {
+        self.dense.as_slice()
+    }
+
+    #[track_caller]
+    pub fn as_mut_slice<T: 'static>(&mut self) -> &mut [T] {
+        self.dense.as_mut_slice()
+    }
+
+    #[track_caller]
+    pub fn iter<T: 'static>(&self) -> impl DoubleEndedIterator<Item = (usize, &T)> + ExactSizeIterator {
+        self.entities.iter().copied().zip(self.dense.iter())
+    }
+
+    #[track_caller]
+    pub fn iter_mut<T: 'static>(&mut self) -> impl DoubleEndedIterator<Item = (usize, &mut T)> + ExactSizeIterator {
+        self.entities.iter().copied().zip(self.dense.iter_mut())
+    }
+
+    /// Keeps the allocated pages.
+    pub fn clear(&mut self) {
+        for page in self.sparse.iter_mut().flatten() {
+            page.fill(EMPTY);
+        }
+        self.entities.clear();
+        self.dense.clear();
+    }
+}
+
+impl<A: Allocator> std::fmt::Debug for BlobSparseSet<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlobSparseSet")
+            .field("type_name", &self.dense.type_name())
+            .field("len", &self.len())
+            .field("pages", &self.sparse.iter().flatten().count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::alloc::Layout;
+    use std::mem::ManuallyDrop;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = BlobSparseSet::new::<String>();
+        assert_eq!(set.insert(3, "three".to_string()), None);
+        assert_eq!(set.insert(5000, "five thousand".to_string()), None);
+        assert_eq!(set.insert(7, "seven".to_string()), None);
+        assert_eq!(set.insert(3, "drei".to_string()).as_deref(), Some("three"));
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(5000) && !set.contains(4999) && !set.contains(usize::MAX - 1));
+        assert_eq!(set.get::<String>(3).map(String::as_str), Some("drei"));
+
+        assert_eq!(set.remove::<String>(3).as_deref(), Some("drei"));
+        assert_eq!(set.remove::<String>(3), None);
+        assert_eq!(set.entities(), &[7, 5000]);
+        assert_eq!(set.get::<String>(7).map(String::as_str), Some("seven"));
+
+        set.get_mut::<String>(7).unwrap().push('!');
+        let pairs = set.iter::<String>().map(|(entity, value)| (entity, value.as_str())).collect::<Vec<_>>();
+        assert_eq!(pairs, [(7, "seven!"), (5000, "five thousand")]);
+
+        assert!(set.remove_and_drop(7));
+        assert!(!set.remove_and_drop(7));
+        assert_eq!(set.get::<String>(5000).map(String::as_str), Some("five thousand"));
+
+        set.clear();
+        assert!(set.is_empty() && !set.contains(5000));
+    }
+
+    #[test]
+    fn type_erased() {
+        let array = unsafe { BlobArray::from_layout(Layout::new::<Vec<u32>>(), crate::drop_fn::<Vec<u32>>(), None, 0) };
+        let mut set = BlobSparseSet::from_array(array);
+
+        for entity in [10, 20, 10] {
+            let value = ManuallyDrop::new(vec![entity as u32]);
+            unsafe { set.insert_raw(entity, (&raw const *value).cast()) };
+        }
+        assert_eq!(set.len(), 2);
+
+        let mut out = std::mem::MaybeUninit::<Vec<u32>>::uninit();
+        assert!(unsafe { set.remove_raw(10, out.as_mut_ptr().cast()) });
+        assert_eq!(unsafe { out.assume_init() }, [10]);
+
+        let ptr = set.get_ptr(20).unwrap();
+        assert_eq!(unsafe { ptr.cast::<Vec<u32>>().as_ref() }, &[20]);
+        assert_eq!(set.entities(), &[20]);
+    }
+}
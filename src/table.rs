@@ -0,0 +1,355 @@
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::ptr::NonNull;
+
+use crate::{BlobArray, BlobArrayError, HeteroBlobArray};
+
+/// A set of [`BlobArray`] columns keyed by component id, where row `i` of every column belongs together.
+/// Rows are only ever added or removed across all columns at once, so the columns always have the same length.
+#[derive(Default)]
+pub struct BlobTable {
+    columns: HashMap<TypeId, BlobArray>,
+    len: usize,
+}
+
+impl BlobTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[track_caller]
+    pub fn with_column<T: 'static>(mut self) -> Self {
+        self.insert_column(TypeId::of::<T>(), BlobArray::new::<T>(0))
+            .unwrap_or_else(|err| err.handle());
+        self
+    }
+
+    /// Adds a column under `id`, which has to hold exactly one value for every existing row.
+    /// The column must have been created for the type `id` stands for, type erased columns are rejected.
+    pub fn insert_column(&mut self, id: TypeId, column: BlobArray) -> Result<(), BlobArrayError> {
+        if column.type_id() != Some(id) || column.len() != self.len || self.columns.contains_key(&id) {
+            return Err(BlobArrayError::ColumnMismatch)
+        }
+
+        self.columns.insert(id, column);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn has_column(&self, id: TypeId) -> bool {
+        self.columns.contains_key(&id)
+    }
+
+    pub fn column_ids(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.columns.keys().copied()
+    }
+
+    pub fn column_by_id(&self, id: TypeId) -> Option<&BlobArray> {
+        self.columns.get(&id)
+    }
+
+    pub fn column<T: 'static>(&self) -> Option<&BlobArray> {
+        self.column_by_id(TypeId::of::<T>())
+    }
+
+    pub fn column_slice<T: 'static>(&self) -> Option<&[T]> {
+        self.column::<T>().map(BlobArray::as_slice)
+    }
+
+    /// Mutable access to a column's elements. The column itself isn't handed out so its length can't change.
+    pub fn column_slice_mut<T: 'static>(&mut self) -> Option<&mut [T]> {
+        self.columns.get_mut(&TypeId::of::<T>()).map(BlobArray::as_mut_slice)
+    }
+
+    pub fn get<T: 'static>(&self, row: usize) -> Option<&T> {
+        self.column::<T>()?.get(row)
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, row: usize) -> Option<&mut T> {
+        self.columns.get_mut(&TypeId::of::<T>())?.get_mut(row)
+    }
+
+    fn check_row(&self, row: usize) -> Result<(), BlobArrayError> {
+        if row < self.len {
+            Ok(())
+        } else {
+            Err(BlobArrayError::IndexOutOfBounds { index: row, len: self.len })
+        }
+    }
+
+    /// Appends a row made of exactly one value per column, keyed by the values' `TypeId`.
+    /// Nothing is written unless the whole row fits, in which case the row is dropped and the table is left untouched.
+    pub fn push_row(&mut self, mut row: HeteroBlobArray) -> Result<(), BlobArrayError> {
+        if row.len() != self.columns.len() {
+            return Err(BlobArrayError::ColumnMismatch)
+        }
+
+        let mut seen = HashSet::with_capacity(row.len());
+        for value in row.iter() {
+            let column = self.columns
+                .get(&value.type_id())
+                .ok_or(BlobArrayError::ColumnMismatch)?;
+
+            if column.type_id() != Some(value.type_id()) {
+                return Err(BlobArrayError::TypeMismatch {
+                    expected: column.type_name(),
+                    found: value.type_name(),
+                })
+            }
+
+            if !seen.insert(value.type_id()) {
+                return Err(BlobArrayError::ColumnMismatch)
+            }
+        }
+
+        // reserve everything up front, so the moves below can't fail halfway through
+        for column in self.columns.values_mut() {
+            column.try_reserve(1)?;
+        }
+
+        unsafe {
+            row.take_all(|id, value| {
+                let column = self.columns.get_mut(&id).unwrap();
+                column.push_raw(value.as_ptr());
+            });
+        }
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes `row` from every column, dropping its values. The last row takes its place.
+    #[track_caller]
+    pub fn swap_remove_row(&mut self, row: usize) {
+        self.check_row(row).unwrap_or_else(|err| err.handle());
+
+        let removed = self.forget_row(row);
+        self.len -= 1;
+
+        unsafe { drop_all(removed) }
+    }
+
+    /// Removes `row` from every column and returns its values. The last row takes its place.
+    pub fn swap_take_row(&mut self, row: usize) -> Option<HeteroBlobArray> {
+        self.check_row(row).ok()?;
+
+        // room for every value plus its worst case padding
+        let bytes = self.columns
+            .values()
+            .map(|column| column.item_layout().size() + column.item_layout().align())
+            .sum();
+        let mut values = HeteroBlobArray::with_capacity(self.columns.len(), bytes);
+
+        // forget the row first, so a panicking push can only leak the values not moved yet, never drop one twice
+        let removed = self.forget_row(row);
+        self.len -= 1;
+
+        for value in removed {
+            let column = &self.columns[&value.id];
+            unsafe {
+                values.push_erased(value.id, column.type_name(), column.item_layout(), value.drop, value.ptr.as_ptr());
+            }
+        }
+
+        Some(values)
+    }
+
+    /// Moves `row` into `other`, whose columns have to be a subset of this table's columns.
+    /// Values of columns `other` doesn't have are dropped. The last row takes the place of the moved one.
+    pub fn move_row_to(&mut self, row: usize, other: &mut BlobTable) -> Result<(), BlobArrayError> {
+        self.check_row(row)?;
+
+        for (id, column) in &other.columns {
+            let source = self.columns.get(id).ok_or(BlobArrayError::ColumnMismatch)?;
+            if source.type_id() != column.type_id() {
+                return Err(BlobArrayError::TypeMismatch {
+                    expected: column.type_name(),
+                    found: source.type_name(),
+                })
+            }
+        }
+
+        for column in other.columns.values_mut() {
+            column.try_reserve(1)?;
+        }
+
+        let mut dropped = Vec::new();
+        for value in self.forget_row(row) {
+            match other.columns.get_mut(&value.id) {
+                Some(column) => unsafe { column.push_raw(value.ptr.as_ptr()) },
+                None => dropped.push(value),
+            }
+        }
+        self.len -= 1;
+        other.len += 1;
+
+        unsafe { drop_all(dropped) }
+
+        Ok(())
+    }
+
+    /// Swap removes `row` from every column without dropping anything, so a panic can't leave the columns out of sync.
+    /// The removed values stay valid until their column is pushed to.
+    fn forget_row(&mut self, row: usize) -> Vec<RemovedValue> {
+        self.columns
+            .iter_mut()
+            .map(|(&id, column)| {
+                debug_assert_eq!(column.len(), self.len, "BlobTable column out of sync");
+                RemovedValue {
+                    id,
+                    ptr: unsafe { column.swap_remove_forget(row) },
+                    drop: column.drop,
+                }
+            })
+            .collect()
+    }
+
+    /// Empties every column before dropping anything, so a panicking destructor leaks the remaining rows
+    /// instead of leaving some columns full.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        let forgotten = self.columns
+            .values_mut()
+            .map(|column| {
+                let len = column.forget_from(0);
+                (column, len)
+            })
+            .collect::<Vec<_>>();
+
+        for (column, len) in forgotten {
+            unsafe { column.drop_range(0, len) }
+        }
+    }
+}
+
+struct RemovedValue {
+    id: TypeId,
+    ptr: NonNull<u8>,
+    drop: Option<unsafe fn(*mut u8)>,
+}
+
+unsafe fn drop_all(values: Vec<RemovedValue>) {
+    for value in values {
+        if let Some(drop) = value.drop {
+            unsafe { drop(value.ptr.as_ptr()) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(f32, f32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(f32, f32);
+
+    fn row(i: u32, name: &Rc<str>) -> HeteroBlobArray {
+        let mut row = HeteroBlobArray::new();
+        row.push(Position(i as f32, 0.0));
+        row.push(name.clone());
+        row.push(i);
+        row
+    }
+
+    fn table() -> BlobTable {
+        BlobTable::new()
+            .with_column::<Position>()
+            .with_column::<Rc<str>>()
+            .with_column::<u32>()
+    }
+
+    #[test]
+    fn push_and_remove_rows() {
+        let name: Rc<str> = Rc::from("entity");
+        let mut table = table();
+        for i in 0..4 {
+            table.push_row(row(i, &name)).unwrap();
+        }
+        assert_eq!(table.len(), 4);
+        assert_eq!(Rc::strong_count(&name), 5);
+
+        let mut bad = row(9, &name);
+        bad.push(Velocity(0.0, 0.0));
+        assert_eq!(table.push_row(bad), Err(BlobArrayError::ColumnMismatch));
+
+        let mut bad = HeteroBlobArray::new();
+        bad.push(1u32);
+        bad.push(2u32);
+        bad.push(name.clone());
+        assert_eq!(table.push_row(bad), Err(BlobArrayError::ColumnMismatch));
+        assert_eq!(Rc::strong_count(&name), 5);
+
+        table.swap_remove_row(0);
+        assert_eq!(table.column_slice::<u32>(), Some(&[3, 1, 2][..]));
+        assert_eq!(table.get::<Position>(0), Some(&Position(3.0, 0.0)));
+        assert_eq!(Rc::strong_count(&name), 4);
+
+        let taken = table.swap_take_row(1).unwrap();
+        assert_eq!(taken.downcast::<u32>().next(), Some(&1));
+        assert_eq!(taken.downcast::<Position>().next(), Some(&Position(1.0, 0.0)));
+        assert_eq!(table.column_slice::<u32>(), Some(&[3, 2][..]));
+        assert!(table.column_ids().all(|id| table.column_by_id(id).unwrap().len() == 2));
+
+        drop(taken);
+        assert_eq!(Rc::strong_count(&name), 3);
+    }
+
+    #[test]
+    fn move_rows() {
+        let name: Rc<str> = Rc::from("entity");
+        let mut from = table();
+        for i in 0..3 {
+            from.push_row(row(i, &name)).unwrap();
+        }
+
+        let mut to = BlobTable::new().with_column::<u32>().with_column::<Position>();
+        from.move_row_to(0, &mut to).unwrap();
+        assert_eq!(from.len(), 2);
+        assert_eq!(to.len(), 1);
+        assert_eq!(to.get::<u32>(0), Some(&0));
+        assert_eq!(from.column_slice::<u32>(), Some(&[2, 1][..]));
+        assert_eq!(Rc::strong_count(&name), 3);
+
+        let mut unrelated = BlobTable::new().with_column::<Velocity>();
+        assert_eq!(from.move_row_to(0, &mut unrelated), Err(BlobArrayError::ColumnMismatch));
+        assert_eq!(from.len(), 2);
+
+        struct Bomb;
+
+        impl Drop for Bomb {
+            fn drop(&mut self) {
+                panic!("boom");
+            }
+        }
+
+        let mut bombs = BlobTable::new().with_column::<Bomb>().with_column::<u32>();
+        let mut bomb_row = HeteroBlobArray::new();
+        bomb_row.push(Bomb);
+        bomb_row.push(7u32);
+        bombs.push_row(bomb_row).unwrap();
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| bombs.clear())).is_err());
+        assert!(bombs.is_empty());
+        assert!(bombs.column_ids().all(|id| bombs.column_by_id(id).unwrap().is_empty()));
+
+        let mut wrong = BlobTable::new();
+        let column = BlobArray::new::<Box<u64>>(0);
+        assert_eq!(wrong.insert_column(TypeId::of::<&'static u64>(), column), Err(BlobArrayError::ColumnMismatch));
+        assert!(wrong.insert_column(TypeId::of::<u32>(), BlobArray::new::<u32>(0)).is_ok());
+
+        if let Some(positions) = from.column_slice_mut::<Position>() {
+            positions.iter_mut().for_each(|position| position.1 = 1.0);
+        }
+        assert_eq!(from.get::<Position>(1), Some(&Position(1.0, 1.0)));
+    }
+}
@@ -0,0 +1,311 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{Allocator, BlobArray};
+
+/// Per element `added` and `changed` ticks, kept parallel to a [`BlobArray`](crate::BlobArray)'s elements.
+#[derive(Clone, Default)]
+pub(crate) struct ChangeTicks {
+    added: Vec<u32>,
+    changed: Vec<u32>,
+    current: u32,
+}
+
+impl ChangeTicks {
+    pub(crate) fn new(len: usize, current: u32) -> Self {
+        Self {
+            added: vec![current; len],
+            changed: vec![current; len],
+            current,
+        }
+    }
+
+    pub(crate) fn current(&self) -> u32 {
+        self.current
+    }
+
+    pub(crate) fn set_current(&mut self, tick: u32) {
+        self.current = tick;
+    }
+
+    pub(crate) fn added(&self) -> &[u32] {
+        &self.added
+    }
+
+    pub(crate) fn changed(&self) -> &[u32] {
+        &self.changed
+    }
+
+    pub(crate) fn changed_mut(&mut self) -> &mut [u32] {
+        &mut self.changed
+    }
+
+    pub(crate) fn push(&mut self) {
+        self.added.push(self.current);
+        self.changed.push(self.current);
+    }
+
+    pub(crate) fn insert(&mut self, index: usize) {
+        self.added.insert(index, self.current);
+        self.changed.insert(index, self.current);
+    }
+
+    pub(crate) fn swap_remove(&mut self, index: usize) {
+        self.added.swap_remove(index);
+        self.changed.swap_remove(index);
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) {
+        self.added.remove(index);
+        self.changed.remove(index);
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.added.truncate(len);
+        self.changed.truncate(len);
+    }
+
+    /// Moves the ticks of element `from` to `to`, mirroring an element being compacted towards the front.
+    pub(crate) fn copy(&mut self, from: usize, to: usize) {
+        self.added[to] = self.added[from];
+        self.changed[to] = self.changed[from];
+    }
+
+    pub(crate) fn copy_within(&mut self, from: std::ops::Range<usize>, to: usize) {
+        self.added.copy_within(from.clone(), to);
+        self.changed.copy_within(from, to);
+    }
+
+    pub(crate) fn mark_changed(&mut self, index: usize) {
+        self.changed[index] = self.current;
+    }
+
+    pub(crate) fn mark_all_changed(&mut self) {
+        self.changed.fill(self.current);
+    }
+
+    pub(crate) fn split_off(&mut self, at: usize) -> Self {
+        Self {
+            added: self.added.split_off(at),
+            changed: self.changed.split_off(at),
+            current: self.current,
+        }
+    }
+
+    /// Appends the ticks of `len` elements coming from another array, which may not be tracking changes.
+    pub(crate) fn append(&mut self, other: Option<&mut ChangeTicks>, len: usize) {
+        match other {
+            Some(other) => {
+                self.added.append(&mut other.added);
+                self.changed.append(&mut other.changed);
+            }
+            None => {
+                self.added.resize(self.added.len() + len, self.current);
+                self.changed.resize(self.changed.len() + len, self.current);
+            }
+        }
+    }
+}
+
+impl<A: Allocator> BlobArray<A> {
+    /// Starts recording, for every element, the tick it was added at and the tick it was last mutably accessed at.
+    /// Existing elements count as added and changed at the current tick.
+    pub fn enable_change_detection(&mut self) {
+        if self.ticks.is_none() {
+            self.ticks = Some(Box::new(ChangeTicks::new(self.len, 0)));
+        }
+    }
+
+    pub fn disable_change_detection(&mut self) {
+        self.ticks = None;
+    }
+
+    pub fn is_detecting_changes(&self) -> bool {
+        self.ticks.is_some()
+    }
+
+    pub fn change_tick(&self) -> Option<u32> {
+        self.ticks.as_ref().map(|ticks| ticks.current())
+    }
+
+    /// Sets the tick stamped on elements as they are pushed or mutably accessed, usually once per frame.
+    pub fn set_change_tick(&mut self, tick: u32) {
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.set_current(tick);
+        }
+    }
+
+    pub fn added_tick(&self, index: usize) -> Option<u32> {
+        self.ticks.as_ref()?.added().get(index).copied()
+    }
+
+    pub fn changed_tick(&self, index: usize) -> Option<u32> {
+        self.ticks.as_ref()?.changed().get(index).copied()
+    }
+
+    /// Write guard over the element at `index`, which only stamps its changed tick once it's actually written through.
+    #[track_caller]
+    pub fn get_cell_mut<T: 'static>(&mut self, index: usize) -> Option<CellMut<'_, T>> {
+        self.assert_type::<T>();
+
+        if index >= self.len { return None }
+
+        let value = unsafe { &mut *self.get_raw::<T>(index).cast::<T>() };
+        let changed = self.ticks.as_deref_mut().map(|ticks| {
+            let current = ticks.current();
+            (&mut ticks.changed_mut()[index], current)
+        });
+        Some(CellMut { value, changed })
+    }
+
+    /// Marks the element at `index` as changed, for writes made through [`BlobArray::get_cell`] or raw pointers.
+    #[track_caller]
+    pub fn set_changed(&mut self, index: usize) {
+        self.check_index(index).unwrap_or_else(|err| err.handle());
+
+        if let Some(ticks) = self.ticks.as_deref_mut() {
+            ticks.mark_changed(index);
+        }
+    }
+
+    /// Iterates over the elements added after `tick`, along with their index.
+    #[track_caller]
+    pub fn iter_added_since<T: 'static>(&self, tick: u32) -> impl DoubleEndedIterator<Item = (usize, &T)> {
+        let ticks = self.expect_ticks();
+        self.iter::<T>()
+            .zip(ticks.added())
+            .enumerate()
+            .filter(move |(_, (_, added))| **added > tick)
+            .map(|(index, (value, _))| (index, value))
+    }
+
+    /// Iterates over the elements added or mutably accessed after `tick`, along with their index.
+    #[track_caller]
+    pub fn iter_changed_since<T: 'static>(&self, tick: u32) -> impl DoubleEndedIterator<Item = (usize, &T)> {
+        let ticks = self.expect_ticks();
+        self.iter::<T>()
+            .zip(ticks.changed())
+            .enumerate()
+            .filter(move |(_, (_, changed))| **changed > tick)
+            .map(|(index, (value, _))| (index, value))
+    }
+
+    #[track_caller]
+    fn expect_ticks(&self) -> &ChangeTicks {
+        self.ticks
+            .as_deref()
+            .expect("change detection is not enabled on this BlobArray")
+    }
+}
+
+/// Mutable access to an element returned by [`BlobArray::get_cell_mut`],
+/// reading leaves the element's changed tick alone, dereferencing it mutably stamps the current tick.
+pub struct CellMut<'a, T> {
+    value: &'a mut T,
+    changed: Option<(&'a mut u32, u32)>,
+}
+
+impl<T> Deref for CellMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for CellMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        if let Some((tick, current)) = &mut self.changed {
+            **tick = *current;
+        }
+        self.value
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for CellMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tracked(len: u32) -> BlobArray {
+        let mut ba = BlobArray::new::<u32>(0);
+        ba.enable_change_detection();
+        ba.set_change_tick(1);
+        ba.extend_from_iter(0..len);
+        ba
+    }
+
+    fn changed(ba: &BlobArray, tick: u32) -> Vec<u32> {
+        ba.iter_changed_since::<u32>(tick).map(|(_, value)| *value).collect()
+    }
+
+    #[test]
+    fn added_and_changed() {
+        let mut ba = tracked(5);
+        ba.set_change_tick(2);
+
+        *ba.get_mut::<u32>(1).unwrap() += 10;
+        ba.push(5u32);
+        assert_eq!(changed(&ba, 1), [11, 5]);
+        assert_eq!(ba.iter_added_since::<u32>(1).map(|(index, _)| index).collect::<Vec<_>>(), [5]);
+
+        ba.set_change_tick(3);
+        ba.iter_mut::<u32>().take(2).for_each(|value| *value += 1);
+        assert_eq!(changed(&ba, 2), [1, 12]);
+
+        ba.set_change_tick(4);
+        ba.set_changed(4);
+        assert_eq!(changed(&ba, 3), [4]);
+        assert_eq!(ba.added_tick(4), Some(1));
+        assert_eq!(ba.changed_tick(4), Some(4));
+
+        ba.set_change_tick(5);
+        let cell = ba.get_cell_mut::<u32>(2).unwrap();
+        assert_eq!(*cell, 2);
+        assert_eq!(changed(&ba, 4), []);
+
+        *ba.get_cell_mut::<u32>(2).unwrap() += 1;
+        assert_eq!(changed(&ba, 4), [3]);
+        assert!(ba.get_cell_mut::<u32>(6).is_none());
+    }
+
+    #[test]
+    fn ticks_follow_elements() {
+        let mut ba = tracked(6);
+        ba.set_change_tick(2);
+        ba.get_mut::<u32>(5);
+
+        // the last element moves into the hole and keeps its ticks
+        ba.swap_remove::<u32>(0);
+        assert_eq!(ba.changed_tick(0), Some(2));
+        assert_eq!(ba.as_slice::<u32>(), &[5, 1, 2, 3, 4]);
+
+        ba.get_mut::<u32>(3);
+        ba.retain::<u32>(|value| value % 2 == 1);
+        assert_eq!(ba.as_slice::<u32>(), &[5, 1, 3]);
+        assert_eq!(changed(&ba, 1), [5, 3]);
+
+        ba.insert(0, 7u32);
+        ba.remove::<u32>(1);
+        ba.drain::<u32>(..1).for_each(drop);
+        assert_eq!(changed(&ba, 1), [3]);
+
+        let mut tail = ba.split_off(1);
+        assert_eq!(tail.iter_changed_since::<u32>(1).count(), 1);
+
+        ba.append(&mut tail);
+        assert_eq!(changed(&ba, 1), [3]);
+        assert_eq!(ba.added_tick(ba.len()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "change detection is not enabled")]
+    fn not_tracking() {
+        let ba = BlobArray::new::<u32>(0);
+        ba.iter_changed_since::<u32>(0).for_each(drop);
+    }
+}
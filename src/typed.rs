@@ -0,0 +1,180 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::{Allocator, BlobArray, Global};
+
+/// Shared view of a [`BlobArray`] whose element type has already been checked to be `T`.
+pub struct TypedBlobArray<'a, T, A: Allocator = Global> {
+    source: &'a BlobArray<A>,
+    marker: PhantomData<&'a [T]>,
+}
+
+impl<'a, T, A: Allocator> TypedBlobArray<'a, T, A> {
+    pub(crate) fn new(source: &'a BlobArray<A>) -> Self {
+        Self {
+            source,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &'a [T] {
+        unsafe { std::slice::from_raw_parts(self.source.block.as_ptr().cast::<T>(), self.source.len) }
+    }
+}
+
+impl<T, A: Allocator> Clone for TypedBlobArray<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A: Allocator> Copy for TypedBlobArray<'_, T, A> {}
+
+impl<T, A: Allocator> Deref for TypedBlobArray<'_, T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: std::fmt::Debug, A: Allocator> std::fmt::Debug for TypedBlobArray<'_, T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for TypedBlobArray<'a, T, A> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+/// Exclusive view of a [`BlobArray`] whose element type has already been checked to be `T`.
+pub struct TypedBlobArrayMut<'a, T, A: Allocator = Global> {
+    source: &'a mut BlobArray<A>,
+    marker: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T, A: Allocator> TypedBlobArrayMut<'a, T, A> {
+    pub(crate) fn new(source: &'a mut BlobArray<A>) -> Self {
+        Self {
+            source,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.source.block.as_ptr().cast::<T>(), self.source.len) }
+    }
+
+    /// Marks every element as changed, like [`BlobArray::as_mut_slice`].
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        if let Some(ticks) = self.source.ticks.as_deref_mut() {
+            ticks.mark_all_changed();
+        }
+        unsafe { std::slice::from_raw_parts_mut(self.source.block.as_ptr().cast::<T>(), self.source.len) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.source.capacity
+    }
+
+    #[track_caller]
+    pub fn push(&mut self, value: T) {
+        unsafe { self.source.try_push_unchecked(value).unwrap_or_else(|err| err.handle()) }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        unsafe { self.source.pop_unchecked() }
+    }
+
+    pub fn clear(&mut self) {
+        self.source.clear()
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.source.truncate(len)
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.retain_mut(|value| f(value))
+    }
+
+    pub fn retain_mut(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        self.source.retain_raw(|ptr| f(unsafe { ptr.cast::<T>().as_mut() }))
+    }
+}
+
+impl<T, A: Allocator> Deref for TypedBlobArrayMut<'_, T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator> DerefMut for TypedBlobArrayMut<'_, T, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: std::fmt::Debug, A: Allocator> std::fmt::Debug for TypedBlobArrayMut<'_, T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for TypedBlobArrayMut<'_, T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        unsafe { self.source.extend_unchecked(iter) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn slices() {
+        let mut ba = BlobArray::new::<u32>(4);
+        for i in 0..4u32 {
+            ba.push(i);
+        }
+
+        assert_eq!(ba.as_slice::<u32>(), &[0, 1, 2, 3]);
+        ba.as_mut_slice::<u32>().reverse();
+        assert_eq!(ba.as_slice::<u32>(), &[3, 2, 1, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "type mismatch")]
+    fn slice_wrong_type() {
+        let ba = BlobArray::new::<u32>(4);
+        ba.as_slice::<i32>();
+    }
+
+    #[test]
+    fn typed_view() {
+        let mut ba = BlobArray::new::<String>(0);
+
+        let mut view = ba.typed_mut::<String>();
+        view.extend(["d", "a", "c", "b", "e"].map(String::from));
+        view.sort();
+        assert_eq!(&view[1..3], ["b", "c"]);
+
+        view.retain(|name| name != "c");
+        assert_eq!(view.pop().as_deref(), Some("e"));
+        view.push("z".to_string());
+        assert_eq!(*view, ["a", "b", "d", "z"]);
+
+        let view = ba.typed::<String>();
+        assert_eq!(view.len(), 4);
+        assert_eq!(view[3], "z");
+        assert_eq!(view.into_iter().map(String::as_str).collect::<String>(), "abdz");
+    }
+}